@@ -0,0 +1,161 @@
+use std::fmt::Write;
+
+use super::matrix::{RangeMatrix, MATRIX_SIZE};
+
+/// Width and height of a cell in SVG charts.
+const CELL_SIZE : usize = 40;
+/// Height of a line of the legend below SVG charts.
+const LEGEND_HEIGHT : usize = 24;
+/// Color of the part of a cell which is in none of the actions.
+const EMPTY_COLOR : &str = "#e8e8e8";
+const DEFAULT_COLOR : &str = "#4caf50";
+
+/// One action of a chart, like raise or call, with the weight it has in every cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartAction {
+    pub name: String,
+    /// Any CSS color, like "#d32f2f" or "green".
+    pub color: String,
+    pub matrix: RangeMatrix,
+}
+
+/// A 13x13 range chart which renders to standalone SVG or an HTML table. Every cell is filled
+/// from the left with the colors of the actions, each one as wide as its weight. What is left
+/// over, like the folding part of a hand, stays grey.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RangeChart {
+    actions: Vec<ChartAction>,
+}
+
+impl RangeChart {
+    /// A chart without any actions, all of its cells are grey.
+    pub fn new() -> RangeChart {
+        RangeChart::default()
+    }
+
+    /// A chart of a single range, shaded by the weights of its cells.
+    pub fn from_matrix(matrix: &RangeMatrix) -> RangeChart {
+        let mut chart = RangeChart::new();
+        chart.add_action("range", DEFAULT_COLOR, matrix.clone());
+        chart
+    }
+
+    /// Adds an action, it is drawn to the right of the ones added before it. If the weights
+    /// of a cell add up to more than 1, all of them are scaled down to fit.
+    pub fn add_action(&mut self, name: &str, color: &str, matrix: RangeMatrix) {
+        self.actions.push(ChartAction { name: name.to_string(), color: color.to_string(), matrix });
+    }
+
+    pub fn get_actions(&self) -> &[ChartAction] {
+        &self.actions
+    }
+
+    /// The chart as a standalone SVG document, with a legend of the actions below the grid.
+    pub fn to_svg(&self) -> String {
+        let width = CELL_SIZE * MATRIX_SIZE;
+        let height = width + LEGEND_HEIGHT * self.actions.len();
+
+        let mut svg = String::new();
+        //writing to a String does not fail
+        writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="sans-serif" font-size="12">"#,
+            width, height, width, height).unwrap();
+
+        for row in 0..MATRIX_SIZE {
+            for col in 0..MATRIX_SIZE {
+                let (x, y) = (col * CELL_SIZE, row * CELL_SIZE);
+                writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    x, y, CELL_SIZE, CELL_SIZE, EMPTY_COLOR).unwrap();
+
+                let mut offset = 0.0;
+                for (action, weight) in self.cell_parts(row, col) {
+                    let part = weight * CELL_SIZE as f64;
+                    writeln!(svg, r#"<rect x="{:.2}" y="{}" width="{:.2}" height="{}" fill="{}"/>"#,
+                        x as f64 + offset, y, part, CELL_SIZE, escape(&action.color)).unwrap();
+                    offset += part;
+                }
+
+                writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="white"/>"#,
+                    x, y, CELL_SIZE, CELL_SIZE).unwrap();
+                writeln!(svg, r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+                    x + CELL_SIZE / 2, y + CELL_SIZE / 2, RangeMatrix::cell_name(row, col)).unwrap();
+            }
+        }
+
+        for (i, action) in self.actions.iter().enumerate() {
+            let y = width + i * LEGEND_HEIGHT;
+            writeln!(svg, r#"<rect x="4" y="{}" width="16" height="16" fill="{}"/>"#, y + 4, escape(&action.color)).unwrap();
+            writeln!(svg, r#"<text x="26" y="{}" dominant-baseline="middle">{}</text>"#, y + 12, escape(&action.name)).unwrap();
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The chart as an HTML table with inline styles, so it can be pasted into any page.
+    /// The weights of each cell are in its title, shown when hovering over it.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<table class=\"range-chart\" style=\"border-collapse: collapse; font-family: sans-serif; font-size: 12px\">\n");
+
+        for row in 0..MATRIX_SIZE {
+            html.push_str("<tr>");
+            for col in 0..MATRIX_SIZE {
+                let parts = self.cell_parts(row, col);
+
+                //hard stops of a gradient draw the actions side by side
+                let mut stops = Vec::new();
+                let mut offset = 0.0;
+                for &(action, weight) in parts.iter() {
+                    let color = escape(&action.color);
+                    stops.push(format!("{} {:.1}%", color, offset * 100.0));
+                    offset += weight;
+                    stops.push(format!("{} {:.1}%", color, offset * 100.0));
+                }
+                stops.push(format!("{} {:.1}%", EMPTY_COLOR, offset * 100.0));
+                stops.push(format!("{} 100%", EMPTY_COLOR));
+
+                let title : Vec<String> = parts.iter()
+                    .map(|&(action, weight)| format!("{}: {:.0}%", escape(&action.name), weight * 100.0))
+                    .collect();
+
+                write!(html, "<td title=\"{}\" style=\"width: 36px; height: 36px; text-align: center; border: 1px solid white; background: linear-gradient(to right, {})\">{}</td>",
+                    title.join(", "), stops.join(", "), RangeMatrix::cell_name(row, col)).unwrap();
+            }
+            html.push_str("</tr>\n");
+        }
+
+        html.push_str("</table>\n");
+        html
+    }
+
+    /// The actions of a cell with their share of its width, scaled down if they add up to more than 1.
+    fn cell_parts(&self, row: usize, col: usize) -> Vec<(&ChartAction, f64)> {
+        let parts : Vec<(&ChartAction, f64)> = self.actions.iter()
+            .map(|action| (action, action.matrix.get(row, col)))
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect();
+
+        let total : f64 = parts.iter().map(|part| part.1).sum();
+        if total > 1.0 {
+            parts.into_iter().map(|(action, weight)| (action, weight / total)).collect()
+        } else {
+            parts
+        }
+    }
+}
+
+/// Escapes text for use in SVG and HTML, inside of elements and attributes.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
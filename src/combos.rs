@@ -0,0 +1,53 @@
+use std::vec;
+
+use cards::card::Card;
+
+use super::Range;
+use super::internal::{RangeComponent, all_classes};
+
+/// Iterates over every combo of a range together with its weight, leaving out combos
+/// with a weight of 0 and those which use any of the dead cards. The combos come
+/// class by class, from 22 up to AA, each one with the higher rank first.
+pub struct ComboIter<'a, R: Range + ?Sized + 'a> {
+    range: &'a R,
+    dead: Vec<Card>,
+    classes: vec::IntoIter<RangeComponent>,
+    combos: vec::IntoIter<(Card, Card)>,
+}
+
+impl<'a, R: Range + ?Sized + 'a> ComboIter<'a, R> {
+    pub fn new(range: &'a R, dead: &[Card]) -> ComboIter<'a, R> {
+        ComboIter {
+            range,
+            dead: dead.to_vec(),
+            classes: all_classes().into_iter(),
+            combos: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<'a, R: Range + ?Sized + 'a> Iterator for ComboIter<'a, R> {
+    type Item = ((Card, Card), f64);
+
+    fn next(&mut self) -> Option<((Card, Card), f64)> {
+        loop {
+            //expand the next class once all combos of the current one are through
+            let (a, b) = match self.combos.next() {
+                Some(combo) => combo,
+                None => {
+                    self.combos = self.classes.next()?.combos().into_iter();
+                    continue;
+                },
+            };
+
+            if self.dead.contains(&a) || self.dead.contains(&b) {
+                continue;
+            }
+
+            let weight = self.range.weight_of((&a, &b));
+            if weight > 0.0 {
+                return Some(((a, b), weight));
+            }
+        }
+    }
+}
@@ -0,0 +1,643 @@
+use rand::{Rng, SeedableRng, XorShiftRng};
+use std::fmt;
+use std::sync::Arc;
+
+use cards::card::Card;
+
+use pokereval::eval_7cards;
+
+use super::{Range, ComboIter};
+use super::error::EquityError;
+use super::mask::{card_index, card_from_index};
+use super::workers::{run_chunks, default_threads, Run};
+
+pub use super::workers::CancelHandle;
+
+/// Number of cards in a deck.
+const DECK_SIZE : usize = 52;
+/// Number of community cards at the showdown.
+const BOARD_SIZE : usize = 5;
+/// Fewest players in a showdown.
+pub const MIN_PLAYERS : usize = 2;
+/// Most players in a showdown, like at a full ring table.
+pub const MAX_PLAYERS : usize = 10;
+/// Showdowns which are sampled with the same random number generator, and before
+/// the precision is checked again.
+const SAMPLE_BATCH : u64 = 1000;
+/// Batches which are sampled in parallel when sampling to a target precision.
+const SAMPLE_ROUND : usize = 64;
+/// Cards at the start of the dealt boards which are the same within one chunk of an exact calculation.
+const CHUNK_PREFIX : usize = 2;
+/// Factor of the standard error for a 95% confidence interval.
+const CONFIDENCE_FACTOR : f64 = 1.96;
+
+/// How one player does against the others.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PlayerEquity {
+    /// Share of the showdowns the player wins alone.
+    pub win: f64,
+    /// Share of the showdowns where the player splits the pot.
+    pub tie: f64,
+    /// Share of the showdowns the player loses.
+    pub lose: f64,
+    /// Share of the pot the player gets on average, split pots are divided evenly.
+    pub equity: f64,
+    /// Standard error of the equity when it was estimated by sampling, 0 when it is exact.
+    pub std_error: f64,
+}
+
+impl PlayerEquity {
+    /// The range which contains the real equity with a probability of 95%.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = CONFIDENCE_FACTOR * self.std_error;
+        ((self.equity - margin).max(0.0), (self.equity + margin).min(1.0))
+    }
+}
+
+/// The equities of all players, in the order their ranges were added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityResult {
+    pub players: Vec<PlayerEquity>,
+    /// How many showdowns were evaluated, one for each combination of hands and board.
+    pub showdowns: u64,
+}
+
+/// A combo of a range with the bit mask of its cards.
+#[derive(Debug, Clone, Copy)]
+struct Hand {
+    cards: (Card, Card),
+    mask: u64,
+    weight: f64,
+}
+
+/// A function which is told how much of a calculation is done.
+#[derive(Clone)]
+struct Progress(Arc<dyn Fn(f64) + Send + Sync>);
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Progress")
+    }
+}
+
+/// Calculates how often ranges win against each other, for 2 to 10 players. Ranges are added one
+/// player after another, combos keep their weight, so "AA:0.5" counts half as much as "KK".
+/// With a board of 3 to 5 cards, the equities are the ones from that street on.
+///
+/// The work is split into chunks which are the same for any number of threads, and their results
+/// are added up in the same order, so a calculation gives the same result on every machine.
+#[derive(Debug, Clone, Default)]
+pub struct EquityCalculator {
+    players: Vec<Vec<Hand>>,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    // 0 for one thread per core
+    threads: usize,
+    cancel: CancelHandle,
+    progress: Option<Progress>,
+}
+
+impl EquityCalculator {
+    pub fn new() -> EquityCalculator {
+        EquityCalculator::default()
+    }
+
+    /// Adds the range of the next player.
+    pub fn add_range<R: Range + ?Sized>(&mut self, range: &R) {
+        let hands = ComboIter::new(range, &[])
+            .map(|((a, b), weight)| Hand { cards: (a, b), mask: card_mask(&[a, b]), weight })
+            .collect();
+        self.players.push(hands);
+    }
+
+    /// Sets the community cards which are known already, the flop, turn or river.
+    /// Combos of the ranges which use any of them are left out of the calculations.
+    pub fn set_board(&mut self, board: &[Card]) -> Result<(), EquityError> {
+        match board.len() {
+            0 | 3..=BOARD_SIZE => {},
+            count => return Err(EquityError::BoardSize { count }),
+        }
+        check_duplicates(board, &self.dead)?;
+        self.board = board.to_vec();
+        Ok(())
+    }
+
+    /// Sets cards which are neither dealt to the board nor in any player's hand, like folded
+    /// hands or burned cards. Combos which use any of them are left out as well.
+    pub fn set_dead_cards(&mut self, dead: &[Card]) -> Result<(), EquityError> {
+        check_duplicates(dead, &self.board)?;
+        self.dead = dead.to_vec();
+        Ok(())
+    }
+
+    pub fn get_board(&self) -> &[Card] {
+        &self.board
+    }
+
+    pub fn get_dead_cards(&self) -> &[Card] {
+        &self.dead
+    }
+
+    /// Sets the number of threads calculations run on, 0 for one thread per core, which is the default.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads;
+    }
+
+    /// Sets a function which is told the share of a calculation which is done, between 0 and 1,
+    /// for progress bars. It is called on the thread which started the calculation.
+    pub fn set_progress<F: Fn(f64) + Send + Sync + 'static>(&mut self, progress: F) {
+        self.progress = Some(Progress(Arc::new(progress)));
+    }
+
+    /// A handle to stop the running calculations of this calculator from another thread. Cancelled
+    /// calculations return EquityError::Cancelled, the ones started later run as usual.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// The exact equities of the ranges, by going through every combination of hands which do
+    /// not share a card and every board that can be dealt to them. This takes long for more than
+    /// a few hands before the flop, see calculate for falling back to sampling.
+    pub fn exact(&self) -> Result<EquityResult, EquityError> {
+        let run = self.cancel.start();
+        let players = self.live_players()?;
+
+        let cards : Vec<Card> = (0..DECK_SIZE).map(card_from_index).collect();
+        let deck = self.remaining_deck();
+        let known_board : Vec<usize> = self.board.iter().map(card_index).collect();
+        let dealt_count = BOARD_SIZE - known_board.len();
+
+        //the chunks are the boards which start with the same cards
+        let mut prefixes : Vec<Vec<usize>> = Vec::new();
+        for_each_board(&deck, dealt_count.min(CHUNK_PREFIX), |prefix| prefixes.push(prefix.to_vec()));
+
+        let chunks = self.run(&run, prefixes.len(), |chunk| {
+            let prefix = &prefixes[chunk];
+            let rest : Vec<usize> = deck.iter().cloned()
+                .filter(|&index| prefix.last().map_or(true, |&last| index > last))
+                .collect();
+
+            let mut tally = Tally::new(players.len());
+            let mut cancelled = false;
+
+            //every board is evaluated once for each hand, the hands are only compared per matchup
+            let mut ranks : Vec<Vec<u16>> = players.iter().map(|hands| vec![0; hands.len()]).collect();
+            let mut matchup_ranks = Vec::with_capacity(players.len());
+            let mut board : Vec<usize> = known_board.iter().chain(prefix.iter()).cloned().collect();
+            for_each_board(&rest, dealt_count - prefix.len(), |dealt| {
+                if cancelled || run.is_cancelled() {
+                    cancelled = true;
+                    return;
+                }
+
+                board.truncate(known_board.len() + prefix.len());
+                board.extend_from_slice(dealt);
+                let board_mask = index_mask(&board[known_board.len()..]);
+                let board_cards = [&cards[board[0]], &cards[board[1]], &cards[board[2]], &cards[board[3]], &cards[board[4]]];
+
+                for (hands, ranks) in players.iter().zip(ranks.iter_mut()) {
+                    for (hand, rank) in hands.iter().zip(ranks.iter_mut()) {
+                        if hand.mask & board_mask == 0 {
+                            *rank = rank_of(hand, &board_cards);
+                        }
+                    }
+                }
+
+                for_each_matchup(&players, board_mask, &mut |matchup| {
+                    matchup_ranks.clear();
+                    matchup_ranks.extend(matchup.iter().enumerate().map(|(player, &hand)| ranks[player][hand]));
+                    let weight = matchup.iter().enumerate().map(|(player, &hand)| players[player][hand].weight).product();
+                    tally.add(&matchup_ranks, weight);
+                    true
+                });
+            });
+
+            if cancelled { None } else { Some(tally) }
+        }, &|done| done as f64 / prefixes.len() as f64)?;
+
+        let mut tally = Tally::new(players.len());
+        for chunk in chunks.iter() {
+            tally.merge(chunk);
+        }
+        tally.result(false)
+    }
+
+    /// The exact equities if there are at most `max_showdowns` of them, otherwise an estimate
+    /// from that many random showdowns.
+    pub fn calculate<R: Rng>(&self, max_showdowns: u64, rng: &mut R) -> Result<EquityResult, EquityError> {
+        let players = self.live_players()?;
+
+        //boards for each combination of hands, so only that many combinations are counted
+        let remaining = (self.remaining_deck().len() - 2 * players.len()) as u64;
+        let boards = choose(remaining, (BOARD_SIZE - self.board.len()) as u64);
+        let max_matchups = max_showdowns / boards;
+        let mut matchups = 0;
+        for_each_matchup(&players, 0, &mut |_| {
+            matchups += 1;
+            matchups <= max_matchups
+        });
+
+        if matchups <= max_matchups {
+            self.exact()
+        } else {
+            self.monte_carlo(max_showdowns, rng)
+        }
+    }
+
+    /// Estimates the equities from a number of random showdowns. Every player's hand is drawn from
+    /// the hands which are left besides the ones drawn before, and the showdown is weighted by how
+    /// much of the ranges was left, so every combination of hands counts exactly as much as in the
+    /// exact calculation, no matter which player holds narrower ranges. Draws which leave a player
+    /// without any hand are not counted as showdowns.
+    pub fn monte_carlo<R: Rng>(&self, samples: u64, rng: &mut R) -> Result<EquityResult, EquityError> {
+        self.sample(samples, None, rng)
+    }
+
+    /// Like monte_carlo, but stops as soon as the standard error of every player's equity
+    /// is at most the target, or after the maximum number of samples.
+    pub fn monte_carlo_until<R: Rng>(&self, target_error: f64, max_samples: u64, rng: &mut R) -> Result<EquityResult, EquityError> {
+        self.sample(max_samples, Some(target_error), rng)
+    }
+
+    fn sample<R: Rng>(&self, max_samples: u64, target_error: Option<f64>, rng: &mut R) -> Result<EquityResult, EquityError> {
+        let run = self.cancel.start();
+        let players = self.live_players()?;
+
+        let cards : Vec<Card> = (0..DECK_SIZE).map(card_from_index).collect();
+        let totals : Vec<f64> = players.iter().map(|hands| hands.iter().map(|hand| hand.weight).sum()).collect();
+        //players with fewer hands go first, so they are less often left without a hand
+        let mut order : Vec<usize> = (0..players.len()).collect();
+        order.sort_by_key(|&player| players[player].len());
+        let known = self.known_mask();
+
+        //every batch has a generator of its own, seeded from its position
+        let seed : u64 = rng.gen();
+        let batches = (max_samples / SAMPLE_BATCH + if max_samples % SAMPLE_BATCH > 0 { 1 } else { 0 }) as usize;
+        let sample_batch = |batch: usize| {
+            let mut rng = batch_rng(seed, batch);
+            let mut board = [&cards[0]; BOARD_SIZE];
+            for (place, card) in board.iter_mut().zip(self.board.iter()) {
+                *place = card;
+            }
+
+            let mut tally = Tally::new(players.len());
+            for _ in 0..SAMPLE_BATCH.min(max_samples - batch as u64 * SAMPLE_BATCH) {
+                if run.is_cancelled() {
+                    return None;
+                }
+
+                let (hands, weight) = match draw_hands(&players, &totals, &order, &mut rng) {
+                    Some(drawn) => drawn,
+                    None => continue,
+                };
+                let used = hands.iter().fold(known, |mask, hand| mask | hand.mask);
+                deal_board(&mut board[self.board.len()..], used, &cards, &mut rng);
+
+                let ranks : Vec<u16> = hands.iter().map(|hand| rank_of(hand, &board)).collect();
+                tally.add(&ranks, weight);
+            }
+            Some(tally)
+        };
+
+        let mut tally = Tally::new(players.len());
+        let target = match target_error {
+            Some(target) => target,
+            None => {
+                let chunks = self.run(&run, batches, sample_batch, &|done| done as f64 / batches as f64)?;
+                for chunk in chunks.iter() {
+                    tally.merge(chunk);
+                }
+                return tally.result(true);
+            },
+        };
+
+        //rounds of batches are sampled in parallel, but the precision is checked after each batch
+        let mut first = 0;
+        'rounds: while first < batches {
+            let count = SAMPLE_ROUND.min(batches - first);
+            let chunks = self.run(&run, count, |batch| sample_batch(first + batch), &|done| (first + done) as f64 / batches as f64)?;
+            for chunk in chunks.iter() {
+                if tally.total > 0.0 && tally.max_std_error() <= target {
+                    break 'rounds;
+                }
+                tally.merge(chunk);
+            }
+            first += count;
+        }
+
+        self.report(1.0);
+        tally.result(true)
+    }
+
+    /// Runs the chunks of a calculation on the threads of the calculator and reports the
+    /// progress, with the share of the work that is done after a number of chunks.
+    fn run<T, F>(&self, run: &Run, chunks: usize, work: F, share_done: &dyn Fn(usize) -> f64) -> Result<Vec<T>, EquityError>
+        where T: Send, F: Fn(usize) -> Option<T> + Sync
+    {
+        let threads = if self.threads == 0 { default_threads() } else { self.threads };
+        run_chunks(chunks, threads, run, &|done| self.report(share_done(done)), work)
+            .ok_or(EquityError::Cancelled)
+    }
+
+    fn report(&self, share: f64) {
+        if let Some(Progress(ref progress)) = self.progress {
+            progress(share);
+        }
+    }
+
+    /// The hands of every player which do not use any card of the board or the dead cards,
+    /// once it is certain that there are showdowns to calculate.
+    fn live_players(&self) -> Result<Vec<Vec<Hand>>, EquityError> {
+        let known = self.known_mask();
+        let players : Vec<Vec<Hand>> = self.players.iter()
+            .map(|hands| hands.iter().filter(|hand| hand.mask & known == 0).cloned().collect())
+            .collect();
+        check_players(&players)?;
+
+        //with many dead cards, the deck can run out before the board is complete
+        if self.remaining_deck().len() < 2 * players.len() + BOARD_SIZE - self.board.len() {
+            return Err(EquityError::NoShowdowns);
+        }
+        Ok(players)
+    }
+
+    /// The cards of the board and the dead cards.
+    fn known_mask(&self) -> u64 {
+        card_mask(&self.board) | card_mask(&self.dead)
+    }
+
+    /// The indices of the cards which are neither on the board nor dead.
+    fn remaining_deck(&self) -> Vec<usize> {
+        let known = self.known_mask();
+        (0..DECK_SIZE).filter(|&index| known & 1 << index == 0).collect()
+    }
+}
+
+/// Checks that there are 2 to 10 players, and any combination of hands which do not share a card.
+fn check_players(players: &[Vec<Hand>]) -> Result<(), EquityError> {
+    if players.len() < MIN_PLAYERS || players.len() > MAX_PLAYERS {
+        return Err(EquityError::PlayerCount { count: players.len() });
+    }
+
+    if for_each_matchup(players, 0, &mut |_| false) {
+        return Err(EquityError::NoShowdowns);
+    }
+    Ok(())
+}
+
+/// Checks that the cards are different from each other and from the other known cards.
+fn check_duplicates(cards: &[Card], others: &[Card]) -> Result<(), EquityError> {
+    for (i, card) in cards.iter().enumerate() {
+        if cards[..i].contains(card) || others.contains(card) {
+            return Err(EquityError::DuplicateCard { card: *card });
+        }
+    }
+    Ok(())
+}
+
+/// Draws a hand for every player in the given order, each one only from the hands which do not
+/// share a card with the ones drawn before, as likely as its weight. To make up for leaving out
+/// those hands, the showdown counts with the share of each player's weight that was left; the
+/// combinations of hands are then exactly as likely as in the exact calculation. None if a player
+/// has no hand left, such draws do not count.
+fn draw_hands<'a, R: Rng>(players: &'a [Vec<Hand>], totals: &[f64], order: &[usize], rng: &mut R) -> Option<(Vec<&'a Hand>, f64)> {
+    let mut used = 0;
+    let mut weight = 1.0;
+    let mut hands : Vec<&Hand> = vec![&players[0][0]; players.len()];
+
+    for &player in order.iter() {
+        let left : f64 = players[player].iter().filter(|hand| hand.mask & used == 0).map(|hand| hand.weight).sum();
+        if left <= 0.0 {
+            return None;
+        }
+        weight *= left / totals[player];
+
+        let mut n = rng.gen_range(0.0, left);
+        let mut drawn = None;
+        for hand in players[player].iter().filter(|hand| hand.mask & used == 0 && hand.weight > 0.0) {
+            drawn = Some(hand);
+            if n < hand.weight {
+                break;
+            }
+            n -= hand.weight;
+        }
+
+        //there is a hand left, it has weight
+        let hand = drawn.unwrap();
+        used |= hand.mask;
+        hands[player] = hand;
+    }
+
+    Some((hands, weight))
+}
+
+/// Calls the function with the position of one hand of every player, for all combinations of hands
+/// which neither share a card with each other nor with the used ones. The function returns whether
+/// to go on, and so does this one: false means it was stopped.
+fn for_each_matchup<F: FnMut(&[usize]) -> bool>(players: &[Vec<Hand>], used: u64, f: &mut F) -> bool {
+    let mut matchup = Vec::with_capacity(players.len());
+    visit_matchups(players, used, &mut matchup, f)
+}
+
+fn visit_matchups<F: FnMut(&[usize]) -> bool>(players: &[Vec<Hand>], used: u64, matchup: &mut Vec<usize>, f: &mut F) -> bool {
+    let player = matchup.len();
+    if player == players.len() {
+        return f(matchup);
+    }
+
+    for (position, hand) in players[player].iter().enumerate() {
+        if hand.mask & used != 0 {
+            continue;
+        }
+        matchup.push(position);
+        let go_on = visit_matchups(players, used | hand.mask, matchup, f);
+        matchup.pop();
+        if !go_on {
+            return false;
+        }
+    }
+    true
+}
+
+/// Adds up the outcomes of showdowns, weighted by how likely the hands are.
+struct Tally {
+    win: Vec<f64>,
+    tie: Vec<f64>,
+    equity: Vec<f64>,
+    // sums of the squared weights, times the pot shares and their squares,
+    // for the variance of sampled equities
+    squared_weights: f64,
+    squared_weight_equity: Vec<f64>,
+    squared_weight_equity_squared: Vec<f64>,
+    total: f64,
+    showdowns: u64,
+}
+
+impl Tally {
+    fn new(players: usize) -> Tally {
+        Tally {
+            win: vec![0.0; players],
+            tie: vec![0.0; players],
+            equity: vec![0.0; players],
+            squared_weights: 0.0,
+            squared_weight_equity: vec![0.0; players],
+            squared_weight_equity_squared: vec![0.0; players],
+            total: 0.0,
+            showdowns: 0,
+        }
+    }
+
+    /// Adds a showdown of hands with the given ranks, higher ranks are better hands.
+    fn add(&mut self, ranks: &[u16], weight: f64) {
+        //there are always players in a showdown
+        let best = *ranks.iter().max().unwrap();
+        let winners = ranks.iter().filter(|&&rank| rank == best).count();
+
+        for (player, &rank) in ranks.iter().enumerate() {
+            if rank == best {
+                if winners == 1 {
+                    self.win[player] += weight;
+                } else {
+                    self.tie[player] += weight;
+                }
+                let share = 1.0 / winners as f64;
+                self.equity[player] += weight * share;
+                self.squared_weight_equity[player] += weight * weight * share;
+                self.squared_weight_equity_squared[player] += weight * weight * share * share;
+            }
+        }
+        self.total += weight;
+        self.squared_weights += weight * weight;
+        self.showdowns += 1;
+    }
+
+    /// Adds the showdowns of another tally.
+    fn merge(&mut self, other: &Tally) {
+        for player in 0..self.win.len() {
+            self.win[player] += other.win[player];
+            self.tie[player] += other.tie[player];
+            self.equity[player] += other.equity[player];
+            self.squared_weight_equity[player] += other.squared_weight_equity[player];
+            self.squared_weight_equity_squared[player] += other.squared_weight_equity_squared[player];
+        }
+        self.total += other.total;
+        self.squared_weights += other.squared_weights;
+        self.showdowns += other.showdowns;
+    }
+
+    /// Standard error of the weighted mean pot share of a player, for sampled showdowns. With
+    /// weights of 1, this is the usual standard deviation of the pot shares over the root of their count.
+    fn std_error(&self, player: usize) -> f64 {
+        if self.total <= 0.0 {
+            return 0.0;
+        }
+        let mean = self.equity[player] / self.total;
+        //the sum of the squared weights times the squared deviations from the mean
+        let deviations = self.squared_weight_equity_squared[player]
+            - 2.0 * mean * self.squared_weight_equity[player]
+            + mean * mean * self.squared_weights;
+        deviations.max(0.0).sqrt() / self.total
+    }
+
+    fn max_std_error(&self) -> f64 {
+        (0..self.win.len()).map(|player| self.std_error(player)).fold(0.0, f64::max)
+    }
+
+    fn result(&self, sampled: bool) -> Result<EquityResult, EquityError> {
+        if self.total <= 0.0 {
+            return Err(EquityError::NoShowdowns);
+        }
+
+        let players = (0..self.win.len()).map(|player| {
+            let win = self.win[player] / self.total;
+            let tie = self.tie[player] / self.total;
+            PlayerEquity {
+                win,
+                tie,
+                lose: (1.0 - win - tie).max(0.0),
+                equity: self.equity[player] / self.total,
+                std_error: if sampled { self.std_error(player) } else { 0.0 },
+            }
+        }).collect();
+
+        Ok(EquityResult { players, showdowns: self.showdowns })
+    }
+}
+
+/// The rank of a hand together with five community cards.
+fn rank_of(hand: &Hand, board: &[&Card; BOARD_SIZE]) -> u16 {
+    eval_7cards(&[&hand.cards.0, &hand.cards.1, board[0], board[1], board[2], board[3], board[4]])
+}
+
+/// The random number generator of a batch of samples, different for every batch.
+fn batch_rng(seed: u64, batch: usize) -> XorShiftRng {
+    let a = split_mix(seed.wrapping_add(2 * batch as u64));
+    let b = split_mix(seed.wrapping_add(2 * batch as u64 + 1));
+    //the generator does not take a seed of only zeros
+    XorShiftRng::from_seed([a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32 | 1])
+}
+
+/// Scrambles the bits of a number, so close numbers give unrelated seeds (SplitMix64).
+fn split_mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Deals random community cards which are not used yet to the places of the board.
+fn deal_board<'a, R: Rng>(places: &mut [&'a Card], mut used: u64, cards: &'a [Card], rng: &mut R) {
+    for place in places.iter_mut() {
+        let mut index = rng.gen_range(0, DECK_SIZE);
+        while used & 1 << index != 0 {
+            index = rng.gen_range(0, DECK_SIZE);
+        }
+        used |= 1 << index;
+        *place = &cards[index];
+    }
+}
+
+/// The number of ways to choose k out of n things.
+fn choose(n: u64, k: u64) -> u64 {
+    (0..k).fold(1, |result, i| result * (n - i) / (i + 1))
+}
+
+fn card_mask(cards: &[Card]) -> u64 {
+    cards.iter().fold(0, |mask, card| mask | 1 << card_index(card))
+}
+
+fn index_mask(indices: &[usize]) -> u64 {
+    indices.iter().fold(0, |mask, &index| mask | 1 << index)
+}
+
+/// Calls the function with every set of `count` cards out of the available ones, as card indices.
+fn for_each_board<F: FnMut(&[usize])>(available: &[usize], count: usize, mut f: F) {
+    if count > available.len() {
+        return;
+    }
+
+    let mut positions : Vec<usize> = (0..count).collect();
+    let mut board : Vec<usize> = positions.iter().map(|&p| available[p]).collect();
+    loop {
+        f(&board);
+
+        //move the last position which can still move one further, the ones after it follow
+        let mut i = count;
+        loop {
+            if i == 0 {
+                return;
+            }
+            i -= 1;
+            if positions[i] < available.len() - count + i {
+                break;
+            }
+        }
+        positions[i] += 1;
+        for j in i + 1..count {
+            positions[j] = positions[j - 1] + 1;
+        }
+        for j in i..count {
+            board[j] = available[positions[j]];
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::error::Error;
+use std::fmt;
+
+use cards::card::Card;
+
+/// Everything that can go wrong while parsing a range string. Each variant carries
+/// the offending token and its byte offset in the input, so it can be pointed at.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RangeParseError {
+    /// The range string did not contain a single component.
+    EmptyInput { token: String, offset: usize },
+    /// There is nothing between two separators, like in "AA,,KK".
+    EmptyComponent { token: String, offset: usize },
+    /// A character where a rank (2-9, T, J, Q, K, A) was expected.
+    UnknownRank { token: String, offset: usize },
+    /// A modifier which is not one of 's', 'o', 'u' or '+', or one in the wrong place.
+    BadModifier { token: String, offset: usize },
+    /// A pair with a suitedness modifier, like "JJs" or "JJo+".
+    InvalidPairModifier { token: String, offset: usize },
+    /// A dash range which is malformed, like "JJ+KK" or "JJ-KQ".
+    BadDashRange { token: String, offset: usize },
+    /// A combo which uses the same card twice, like "AhAh".
+    DuplicateCard { token: String, offset: usize },
+    /// An exclusion of hands which were not part of the range up to that point, like "!55" in "AA,!55".
+    ExclusionNotInRange { token: String, offset: usize },
+    /// Exclusions removed every single hand from the range.
+    EmptyRange { token: String, offset: usize },
+    /// A weight which is malformed or not between 0 and 1 (0% and 100%), or a bracket group
+    /// which is not closed properly, like in "[50]AA[/40]".
+    BadWeight { token: String, offset: usize },
+    /// A weight in a range type which does not support them, use a WeightedRange instead.
+    UnexpectedWeight { token: String, offset: usize },
+    /// A hand which appears twice in a hand ranking.
+    DuplicateHand { token: String, offset: usize },
+    /// A component which is too short or too long to mean anything.
+    UnrecognizedComponent { token: String, offset: usize },
+}
+
+impl RangeParseError {
+    /// The part of the input which caused the error.
+    pub fn token(&self) -> &str {
+        match *self {
+            RangeParseError::EmptyInput { ref token, .. } |
+            RangeParseError::EmptyComponent { ref token, .. } |
+            RangeParseError::UnknownRank { ref token, .. } |
+            RangeParseError::BadModifier { ref token, .. } |
+            RangeParseError::InvalidPairModifier { ref token, .. } |
+            RangeParseError::BadDashRange { ref token, .. } |
+            RangeParseError::DuplicateCard { ref token, .. } |
+            RangeParseError::ExclusionNotInRange { ref token, .. } |
+            RangeParseError::EmptyRange { ref token, .. } |
+            RangeParseError::BadWeight { ref token, .. } |
+            RangeParseError::UnexpectedWeight { ref token, .. } |
+            RangeParseError::DuplicateHand { ref token, .. } |
+            RangeParseError::UnrecognizedComponent { ref token, .. } => token,
+        }
+    }
+
+    /// Byte offset of the token in the input string.
+    pub fn offset(&self) -> usize {
+        match *self {
+            RangeParseError::EmptyInput { offset, .. } |
+            RangeParseError::EmptyComponent { offset, .. } |
+            RangeParseError::UnknownRank { offset, .. } |
+            RangeParseError::BadModifier { offset, .. } |
+            RangeParseError::InvalidPairModifier { offset, .. } |
+            RangeParseError::BadDashRange { offset, .. } |
+            RangeParseError::DuplicateCard { offset, .. } |
+            RangeParseError::ExclusionNotInRange { offset, .. } |
+            RangeParseError::EmptyRange { offset, .. } |
+            RangeParseError::BadWeight { offset, .. } |
+            RangeParseError::UnexpectedWeight { offset, .. } |
+            RangeParseError::DuplicateHand { offset, .. } |
+            RangeParseError::UnrecognizedComponent { offset, .. } => offset,
+        }
+    }
+
+    fn description_text(&self) -> &'static str {
+        match *self {
+            RangeParseError::EmptyInput { .. } => "zero length range string not allowed",
+            RangeParseError::EmptyComponent { .. } => "empty range component",
+            RangeParseError::UnknownRank { .. } => "unknown rank",
+            RangeParseError::BadModifier { .. } => "bad modifier",
+            RangeParseError::InvalidPairModifier { .. } => "pairs can not be suited or offsuit",
+            RangeParseError::BadDashRange { .. } => "bad dash range",
+            RangeParseError::DuplicateCard { .. } => "the same card can not be used twice",
+            RangeParseError::ExclusionNotInRange { .. } => "excluded hands are not in the range",
+            RangeParseError::EmptyRange { .. } => "no hands left in the range",
+            RangeParseError::BadWeight { .. } => "bad weight",
+            RangeParseError::UnexpectedWeight { .. } => "weights are not supported by this range",
+            RangeParseError::DuplicateHand { .. } => "hand ranked more than once",
+            RangeParseError::UnrecognizedComponent { .. } => "unrecognized range component",
+        }
+    }
+}
+
+impl fmt::Display for RangeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} '{}' at offset {}", self.description_text(), self.token(), self.offset())
+    }
+}
+
+impl Error for RangeParseError {}
+
+/// Everything that can go wrong while drawing a hand from a range.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DrawError {
+    /// Every combo of the range uses at least one of the dead cards.
+    NoCombosLeft,
+}
+
+impl fmt::Display for DrawError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DrawError::NoCombosLeft => write!(f, "no combo of the range is left besides the dead cards"),
+        }
+    }
+}
+
+impl Error for DrawError {}
+
+/// Everything that can go wrong while calculating equities.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EquityError {
+    /// The calculation does not support this number of players.
+    PlayerCount { count: usize },
+    /// Every combination of hands shares a card, so there is not a single showdown.
+    NoShowdowns,
+    /// A board has 3, 4 or 5 cards, or none before the flop.
+    BoardSize { count: usize },
+    /// A card is on the board or among the dead cards more than once.
+    DuplicateCard { card: Card },
+    /// The calculation was stopped with its CancelHandle.
+    Cancelled,
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EquityError::PlayerCount { count } => write!(f, "equities can not be calculated for {} players", count),
+            EquityError::NoShowdowns => write!(f, "every combination of hands shares a card"),
+            EquityError::BoardSize { count } => write!(f, "a board can not have {} cards", count),
+            EquityError::DuplicateCard { card } => write!(f, "card {} is known more than once", card),
+            EquityError::Cancelled => write!(f, "the calculation was cancelled"),
+        }
+    }
+}
+
+impl Error for EquityError {}
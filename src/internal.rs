@@ -1,8 +1,126 @@
-use cards::card::{Value};
+use std::cmp::{min, max};
+
+use rand::Rng;
+
+use cards::card::{Card, Value, Suit};
+
+use utils::{gen_random_suits, CardValueIterator};
+
+const SUITS : [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Ord, PartialOrd, Hash)]
 pub enum RangeComponent {
     Pair(Value),
     CardsSuited(Value, Value), // AJs
-    CardsUnsuited(Value, Value) // AQo
+    CardsUnsuited(Value, Value), // AQo
+    Combo(Card, Card), // AhKh, the greater card first
+}
+
+impl RangeComponent {
+    /// A single combo. The order of the cards does not matter.
+    pub fn combo(a: Card, b: Card) -> RangeComponent {
+        RangeComponent::Combo(max(a, b), min(a, b))
+    }
+
+    /// The pair, suited or unsuited component two cards belong to.
+    pub fn class_of(a: &Card, b: &Card) -> RangeComponent {
+        let value_greater = max(a.value, b.value);
+        let value_lesser = min(a.value, b.value);
+
+        if a.value == b.value {
+            RangeComponent::Pair(value_greater)
+        } else if a.suit == b.suit {
+            RangeComponent::CardsSuited(value_greater, value_lesser)
+        } else {
+            RangeComponent::CardsUnsuited(value_greater, value_lesser)
+        }
+    }
+
+    /// How many different two card combinations are covered.
+    pub fn combination_count(&self) -> usize {
+        match *self {
+            RangeComponent::Pair(_) => 6,
+            RangeComponent::CardsSuited(_, _) => 4,
+            RangeComponent::CardsUnsuited(_, _) => 12,
+            RangeComponent::Combo(_, _) => 1,
+        }
+    }
+
+    /// All two card combinations of the component, the greater card first.
+    pub fn combos(&self) -> Vec<(Card, Card)> {
+        let mut combos = Vec::with_capacity(self.combination_count());
+
+        match *self {
+            RangeComponent::Pair(val) => {
+                for (i, &suit_one) in SUITS.iter().enumerate() {
+                    for &suit_two in SUITS[i + 1..].iter() {
+                        combos.push((Card::new(val, suit_one), Card::new(val, suit_two)));
+                    }
+                }
+            },
+            RangeComponent::CardsSuited(val_g, val_l) => {
+                for &suit in SUITS.iter() {
+                    combos.push((Card::new(val_g, suit), Card::new(val_l, suit)));
+                }
+            },
+            RangeComponent::CardsUnsuited(val_g, val_l) => {
+                for &suit_one in SUITS.iter() {
+                    for &suit_two in SUITS.iter().filter(|&&s| s != suit_one) {
+                        combos.push((Card::new(val_g, suit_one), Card::new(val_l, suit_two)));
+                    }
+                }
+            },
+            RangeComponent::Combo(a, b) => {
+                combos.push((a, b));
+            },
+        }
+
+        combos
+    }
+
+    /// Draws one of the combinations of the component, each one is equally likely.
+    pub fn random_combo<R: Rng>(&self, rng: &mut R) -> (Card, Card) {
+        match *self {
+            RangeComponent::Pair(val) => {
+                let (suit_one, suit_two) = gen_random_suits(rng);
+
+                let c1 = Card::new(val, suit_one);
+                let c2 = Card::new(val, suit_two);
+                (c1, c2)
+            },
+            RangeComponent::CardsSuited(val_g,val_l) => {
+                let (suit_one, _) = gen_random_suits(rng);
+                let c1 = Card::new(val_g, suit_one);
+                let c2 = Card::new(val_l, suit_one);
+                (c1, c2)
+            },
+            RangeComponent::CardsUnsuited(val_g,val_l) => {
+                let (suit_one, suit_two) = gen_random_suits(rng);
+                let c1 = Card::new(val_g, suit_one);
+                let c2 = Card::new(val_l, suit_two);
+                (c1, c2)
+            },
+            RangeComponent::Combo(c1, c2) => (c1, c2),
+        }
+    }
+
+    pub fn contains(&self, hand: (&Card, &Card)) -> bool {
+        match *self {
+            RangeComponent::Combo(_, _) => *self == RangeComponent::combo(*hand.0, *hand.1),
+            _ => *self == RangeComponent::class_of(hand.0, hand.1),
+        }
+    }
+}
+
+/// All 169 pair, suited and unsuited components.
+pub fn all_classes() -> Vec<RangeComponent> {
+    let mut classes = Vec::with_capacity(169);
+    for value_greater in CardValueIterator::new(Value::Two, Value::Ace) {
+        classes.push(RangeComponent::Pair(value_greater));
+        for value_lesser in CardValueIterator::new(Value::Two, value_greater).take_while(|&v| v != value_greater) {
+            classes.push(RangeComponent::CardsSuited(value_greater, value_lesser));
+            classes.push(RangeComponent::CardsUnsuited(value_greater, value_lesser));
+        }
+    }
+    classes
 }
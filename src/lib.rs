@@ -12,165 +12,357 @@ extern crate pokereval;
 mod utils;
 mod parse;
 mod internal;
+mod error;
+mod weighted;
+mod text;
+mod mask;
+mod ranking;
+mod stats;
+mod combos;
+mod sampling;
+mod matrix;
+mod chart;
+mod workers;
+
+pub mod equity;
 
 use rand::{Rng};
-use std::cmp::{min, max};
 use std::collections::BTreeSet;
-use std::io::{Error, ErrorKind};
+use std::fmt;
+use std::ops::{BitOr, BitAnd, Sub, Not};
+use std::str::FromStr;
 
 use cards::card::{Card};
 
-use utils::gen_random_suits;
-use parse::{parse_5_chars, parse_4_chars, parse_3_chars, parse_2_chars};
+use parse::{tokenize, parse_component};
 use internal::RangeComponent;
+use text::{canonical_text, EMPTY_TEXT};
+use mask::{ComboMask, combo_index, combo_from_index};
+use stats::count_combos;
+use sampling::sample;
+
+pub use error::{RangeParseError, DrawError, EquityError};
+pub use weighted::WeightedRange;
+pub use ranking::HandRanking;
+pub use stats::ComboCounts;
+pub use combos::ComboIter;
+pub use sampling::Sampling;
+pub use matrix::RangeMatrix;
+pub use chart::{RangeChart, ChartAction};
 
 /// Any range should be able to get checked whether a hand can be in it and to draw a random card sample.
+/// Random number generators are passed as `&mut dyn Rng`, so ranges can be used as trait objects,
+/// like `Vec<Box<dyn Range>>`.
 pub trait Range {
     fn contains(&self, hand: (&Card, &Card)) -> bool;
-    fn draw(&self) -> (Card, Card);
+
+    /// Draw a hand with the given random number generator, so seeded generators give the same hands.
+    /// Panics if the range has no hands, like an empty SimpleRange; draw_excluding_with returns an error instead.
+    fn draw_with(&self, rng: &mut dyn Rng) -> (Card, Card);
+
+    /// Draw a hand. Panics if the range has no hands, like draw_with.
+    fn draw(&self) -> (Card, Card) {
+        self.draw_with(&mut rand::thread_rng())
+    }
+
+    /// The weight of a hand in the range, between 0 and 1. Unweighted ranges only know those two.
+    fn weight_of(&self, hand: (&Card, &Card)) -> f64 {
+        if self.contains(hand) { 1.0 } else { 0.0 }
+    }
+
+    /// The combos of the range, split into pairs, suited and offsuit hands.
+    fn combo_counts(&self) -> ComboCounts {
+        count_combos(self, &[])
+    }
+
+    /// The combos of the range which remain when the dead cards, like hole cards or
+    /// the board, are known to be somewhere else.
+    fn combo_counts_excluding(&self, dead: &[Card]) -> ComboCounts {
+        count_combos(self, dead)
+    }
+
+    /// Every combo of the range with its weight. Trait objects use `ComboIter::new(range, &[])`.
+    fn iter_combos<'a>(&'a self) -> ComboIter<'a, Self> where Self: Sized {
+        ComboIter::new(self, &[])
+    }
+
+    /// Every combo of the range with its weight, which does not use any of the dead cards.
+    fn iter_combos_excluding<'a>(&'a self, dead: &[Card]) -> ComboIter<'a, Self> where Self: Sized {
+        ComboIter::new(self, dead)
+    }
+
+    /// All combos of the range which do not use any of the dead cards, the higher rank first.
+    fn combos_excluding(&self, dead: &[Card]) -> Vec<(Card, Card)> {
+        ComboIter::new(self, dead).map(|item| item.0).collect()
+    }
+
+    /// Draw a hand which does not use any of the dead cards, like the hand of another player
+    /// or the board. Each combo is as likely as its weight.
+    fn draw_excluding(&self, dead: &[Card]) -> Result<(Card, Card), DrawError> {
+        self.draw_excluding_with(dead, &mut rand::thread_rng())
+    }
+
+    /// Like draw_excluding, with the given random number generator.
+    fn draw_excluding_with(&self, dead: &[Card], rng: &mut dyn Rng) -> Result<(Card, Card), DrawError> {
+        self.draw_with_sampling(&Sampling::Combinatorial, dead, rng)
+    }
+
+    /// Draw a hand which does not use any of the dead cards, with another idea of how likely
+    /// hands are, like showing every hand class equally often.
+    fn draw_with_sampling(&self, sampling: &Sampling, dead: &[Card], mut rng: &mut dyn Rng) -> Result<(Card, Card), DrawError> {
+        sample(self, sampling, dead, &mut rng)
+    }
 }
 
 /// The simplest of range types.
+#[derive(Debug, Clone)]
 pub struct SimpleRange {
     text: String,
     components: BTreeSet<RangeComponent>,
-    combination_lookup: Vec<(usize, RangeComponent)>,
-    combination_count: usize,
+    // one bit for each of the 1326 combos, for constant time lookups
+    mask: ComboMask,
+    // indices of the combos in the mask, to draw from
+    combos: Vec<usize>,
 }
 
 impl SimpleRange {
     /// Construct a range from a non-empty string of the kind "AA,AJs+". For more components, see the README.
-    pub fn new_from_string(ranges: &str) -> Result<SimpleRange, Error> {
-        let raw_components = ranges.split(",");
-        let mut components : BTreeSet<RangeComponent> = BTreeSet::new();
+    /// Ranks are case-insensitive and components can be separated by ',', ';' or whitespace.
+    /// Components starting with '!' are removed from the range, like in "22+,!55".
+    /// "none" is the empty range, which is how empty ranges are displayed.
+    pub fn new_from_string(ranges: &str) -> Result<SimpleRange, RangeParseError> {
+        if ranges.trim().is_empty() {
+            return Err(RangeParseError::EmptyInput {
+                token: String::new(), offset: 0 });
+        }
+
+        if ranges.trim().eq_ignore_ascii_case(EMPTY_TEXT) {
+            return Ok(SimpleRange::from_mask(String::from(ranges), ComboMask::empty()));
+        }
+
+        let mut mask = ComboMask::empty();
 
         //parse every single range component
-        for component in raw_components {
-            let chars : Vec<char> = component.chars().collect();
-            let new_components = match chars.len() {
-                5 => { try!(parse_5_chars(chars)) },
-                4 => { try!(parse_4_chars(chars)) },
-                3 => { try!(parse_3_chars(chars)) },
-                2 => { try!(parse_2_chars(chars)) },
-                _ => { return Err(Error::new(ErrorKind::Other,
-                  format!("Too many characters in pattern '{}'", component))); }
-            };
-
-            for new_component in new_components {
-                components.insert(new_component);
+        //from left to right, exclusions only remove what was added before them
+        for token in tokenize(ranges)? {
+            let new_mask = ComboMask::from_components(&parse_component(&token)?);
+
+            if token.weight.is_some() {
+                return Err(RangeParseError::UnexpectedWeight {
+                    token: token.text.to_string(), offset: token.offset });
+            }
+
+            if token.negated {
+                if !new_mask.is_subset(&mask) {
+                    return Err(RangeParseError::ExclusionNotInRange {
+                        token: token.text.to_string(), offset: token.offset });
+                }
+                mask = mask.difference(&new_mask);
+            } else {
+                mask = mask.union(&new_mask);
             }
         }
 
-        if components.len() == 0 {
-            return Err(Error::new(ErrorKind::Other,
-                "Zero length range string not allowed"));
+        if mask.is_empty() {
+            return Err(RangeParseError::EmptyRange {
+                token: String::from(ranges), offset: 0 });
         }
 
+        Ok(SimpleRange::from_mask(String::from(ranges), mask))
+    }
 
-        //construct an array with a running sum of combinations
-        //this is used later to draw cards from the range, weighted by their
-        //probability weighted by number of possible combinations
-        // pair: 6 combos
-        // suited cards:    4
-        //   one possibility for each suit
-        // unsuited cards:  4 * 3
-        //   4 suits for first card, for each 3 other suits for second
-        let mut combination_count = 0;
-        let mut combination_lookup : Vec<(usize, RangeComponent)> = Vec::new();
-
-        for component in components.iter() {
-            match *component {
-                RangeComponent::Pair(_) => { combination_count+=6; },
-                RangeComponent::CardsSuited(_, _) => { combination_count+=4; },
-                RangeComponent::CardsUnsuited(_, _) => { combination_count+=12; },
-            }
-            combination_lookup.push( (combination_count, component.clone() ) );
+    /// The components are derived from the mask, so they are always normalized.
+    fn from_mask(text: String, mask: ComboMask) -> SimpleRange {
+        SimpleRange {
+            text,
+            components: mask.components(),
+            combos: mask.indices(),
+            mask,
         }
+    }
+
+    /// Ranges which are the result of set operations are written in their canonical text.
+    fn from_computed_mask(mask: ComboMask) -> SimpleRange {
+        let mut range = SimpleRange::from_mask(String::new(), mask);
+        range.text = range.get_canonical_text();
+        range
+    }
 
-        Ok(SimpleRange {
-            text: String::from(ranges),
-            components: components,
-            combination_lookup: combination_lookup,
-            combination_count: combination_count,
-        })
+    /// The best hands of a ranking which make up at least the given percentage of all 1326 combos,
+    /// so the range can be a little wider than asked for. Percentages are clamped to 0 to 100,
+    /// NaN counts as 0.
+    pub fn top_percent(percent: f64, ranking: &HandRanking) -> SimpleRange {
+        let percent = if percent.is_nan() { 0.0 } else { percent.clamp(0.0, 100.0) };
+        let classes = ranking.top_classes(percent);
+        SimpleRange::from_computed_mask(ComboMask::from_components(&classes))
     }
 
+    /// All hands which are in either of the ranges.
+    pub fn union(&self, other: &SimpleRange) -> SimpleRange {
+        SimpleRange::from_computed_mask(self.mask.union(&other.mask))
+    }
+
+    /// All hands which are in both ranges.
+    pub fn intersection(&self, other: &SimpleRange) -> SimpleRange {
+        SimpleRange::from_computed_mask(self.mask.intersection(&other.mask))
+    }
+
+    /// All hands of this range which are not in the other one.
+    pub fn difference(&self, other: &SimpleRange) -> SimpleRange {
+        SimpleRange::from_computed_mask(self.mask.difference(&other.mask))
+    }
+
+    /// All hands which are not in the range.
+    pub fn complement(&self) -> SimpleRange {
+        SimpleRange::from_computed_mask(self.mask.complement())
+    }
+
+    /// Set operations, top_percent(0.0, ..) and empty matrices give ranges without any hands.
+    /// They are written as "none" and drawing from them panics.
+    pub fn is_empty(&self) -> bool {
+        self.mask.is_empty()
+    }
+
+    /// The text the range was constructed from.
     pub fn get_range_text(&self) -> String {
         self.text.clone()
     }
 
+    /// The range in the shortest usual notation, the same for equal ranges.
+    pub fn get_canonical_text(&self) -> String {
+        canonical_text(&self.components)
+    }
+
     pub fn get_component_count(&self) -> usize {
         self.components.len()
     }
+
+    /// How many of the 1326 combos are in the range.
+    pub fn get_combo_count(&self) -> usize {
+        self.combos.len()
+    }
+}
+
+/// Ranges are equal if they contain the same hands, no matter how they were written.
+impl PartialEq for SimpleRange {
+    fn eq(&self, other: &SimpleRange) -> bool {
+        self.mask == other.mask
+    }
+}
+
+impl Eq for SimpleRange {}
+
+/// Writes the canonical text of the range.
+impl fmt::Display for SimpleRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_canonical_text())
+    }
+}
+
+impl FromStr for SimpleRange {
+    type Err = RangeParseError;
+
+    fn from_str(s: &str) -> Result<SimpleRange, RangeParseError> {
+        SimpleRange::new_from_string(s)
+    }
+}
+
+impl<'a> BitOr<&'a SimpleRange> for &'a SimpleRange {
+    type Output = SimpleRange;
+
+    fn bitor(self, other: &SimpleRange) -> SimpleRange {
+        self.union(other)
+    }
+}
+
+impl BitOr for SimpleRange {
+    type Output = SimpleRange;
+
+    fn bitor(self, other: SimpleRange) -> SimpleRange {
+        self.union(&other)
+    }
+}
+
+impl<'a> BitAnd<&'a SimpleRange> for &'a SimpleRange {
+    type Output = SimpleRange;
+
+    fn bitand(self, other: &SimpleRange) -> SimpleRange {
+        self.intersection(other)
+    }
+}
+
+impl BitAnd for SimpleRange {
+    type Output = SimpleRange;
+
+    fn bitand(self, other: SimpleRange) -> SimpleRange {
+        self.intersection(&other)
+    }
+}
+
+impl<'a> Sub<&'a SimpleRange> for &'a SimpleRange {
+    type Output = SimpleRange;
+
+    fn sub(self, other: &SimpleRange) -> SimpleRange {
+        self.difference(other)
+    }
+}
+
+impl Sub for SimpleRange {
+    type Output = SimpleRange;
+
+    fn sub(self, other: SimpleRange) -> SimpleRange {
+        self.difference(&other)
+    }
+}
+
+impl Not for &SimpleRange {
+    type Output = SimpleRange;
+
+    fn not(self) -> SimpleRange {
+        self.complement()
+    }
+}
+
+impl Not for SimpleRange {
+    type Output = SimpleRange;
+
+    fn not(self) -> SimpleRange {
+        self.complement()
+    }
 }
 
 impl Range for SimpleRange {
     /// Checks whether a card is in the range.
     fn contains(&self, hand: (&Card, &Card)) -> bool {
-        let value_greater = max(hand.0.value, hand.1.value);
-        let value_lesser = min(hand.0.value, hand.1.value);
-
-        let mut ita = self.components.iter();
-
-        let result = if hand.0.value == hand.1.value {
-            ita.any(|&x| match x {
-                RangeComponent::Pair(val) => val == value_greater,
-                _ => false
-            })
-        } else if hand.0.suit == hand.1.suit {
-            ita.any(|&x| match x {
-                RangeComponent::CardsSuited(val_g, val_l) => val_g == value_greater && val_l == value_lesser,
-                _ => false
-            })
-        } else {
-            //filter for non-suited
-            ita.any(|&x| match x {
-                RangeComponent::CardsUnsuited(val_g, val_l) => val_g == value_greater && val_l == value_lesser,
-                _ => false
-            })
-        };
-
-        result
-    }
-
-    /// Draw a hand from the range, weighted by their combinatoric probability. Another option would be uniform probability for any component.
-    fn draw(&self) -> (Card, Card) {
-        let mut rng = rand::thread_rng();
-        let n: usize = rng.gen_range(0, self.combination_count); // a random number that falls into the range of enumerated combinations
+        //the same card twice is no combo, it is only looked at as a pair, suited or unsuited hand
+        if hand.0 == hand.1 {
+            return self.components.iter().any(|x| x.contains(hand));
+        }
+        self.mask.contains(combo_index(hand.0, hand.1))
+    }
 
-        let mut speculative_component : Option<RangeComponent> = None;
-        for item in self.combination_lookup.iter() {
-            let (cummulative_combination_count, component) = *item;
+    /// Draw a hand from the range, weighted by their combinatoric probability. For other options, see draw_with_sampling.
+    fn draw_with(&self, mut rng: &mut dyn Rng) -> (Card, Card) {
+        let n: usize = (&mut rng).gen_range(0, self.combos.len()); // every combination is equally likely
 
-            if n < cummulative_combination_count {
-                speculative_component = Some(component);
-                break;
-            }
+        combo_from_index(self.combos[n])
+    }
+
+    /// Like draw, but without the combos which use any of the dead cards.
+    fn draw_excluding_with(&self, dead: &[Card], mut rng: &mut dyn Rng) -> Result<(Card, Card), DrawError> {
+        let combos : Vec<(Card, Card)> = self.combos_excluding(dead);
+        if combos.is_empty() {
+            return Err(DrawError::NoCombosLeft);
         }
 
-        //TODO: this might be a bit crude (the unwrapping)
-        let range_component = speculative_component.unwrap();
-        let cards = match range_component {
-            RangeComponent::Pair(val) => {
-                let (suit_one, suit_two) = gen_random_suits(&mut rng);
-
-                let c1 = Card::new(val, suit_one);
-                let c2 = Card::new(val, suit_two);
-                (c1, c2)
-            },
-            RangeComponent::CardsSuited(val_g,val_l) => {
-                let (suit_one, _) = gen_random_suits(&mut rng);
-                let c1 = Card::new(val_g, suit_one);
-                let c2 = Card::new(val_l, suit_one);
-                (c1, c2)
-            },
-            RangeComponent::CardsUnsuited(val_g,val_l) => {
-                let (suit_one, suit_two) = gen_random_suits(&mut rng);
-                let c1 = Card::new(val_g, suit_one);
-                let c2 = Card::new(val_l, suit_two);
-                (c1, c2)
-            },
-        };
-        cards
+        let n: usize = (&mut rng).gen_range(0, combos.len());
+        Ok(combos[n])
+    }
+
+    fn combos_excluding(&self, dead: &[Card]) -> Vec<(Card, Card)> {
+        self.combos.iter()
+            .map(|&index| combo_from_index(index))
+            .filter(|&(a, b)| !dead.contains(&a) && !dead.contains(&b))
+            .collect()
     }
 }
@@ -0,0 +1,137 @@
+use std::collections::BTreeSet;
+
+use cards::card::{Card, Suit};
+
+use super::internal::{RangeComponent, all_classes};
+use super::utils::{card_value_index, card_value_from_index};
+
+/// Number of different two card combinations, 52 choose 2.
+pub const COMBO_COUNT : usize = 1326;
+
+const WORDS : usize = (COMBO_COUNT + 63) / 64;
+
+/// Position of a card in a deck sorted by value, then by suit. From 0 for 2s up to 51 for Ac.
+pub fn card_index(card: &Card) -> usize {
+    card_value_index(card.value) * 4 + card.suit as usize
+}
+
+pub fn card_from_index(index: usize) -> Card {
+    let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+    //the value is always there for indices below 52
+    Card::new(card_value_from_index(index / 4).unwrap(), suits[index % 4])
+}
+
+/// Position of two different cards among all 1326 combos. The order of the cards does not matter.
+pub fn combo_index(a: &Card, b: &Card) -> usize {
+    let (i, j) = (card_index(a), card_index(b));
+    let (lower, greater) = if i < j { (i, j) } else { (j, i) };
+    greater * (greater - 1) / 2 + lower
+}
+
+/// The two cards of a combo index, the greater card first.
+pub fn combo_from_index(index: usize) -> (Card, Card) {
+    //the greater card is the largest one whose combos start at or before the index
+    let mut greater = 1;
+    while (greater + 1) * greater / 2 <= index {
+        greater += 1;
+    }
+    let lower = index - greater * (greater - 1) / 2;
+    (card_from_index(greater), card_from_index(lower))
+}
+
+/// A set of combos with one bit for each of the 1326 of them.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ComboMask {
+    bits: [u64; WORDS],
+}
+
+impl ComboMask {
+    pub fn empty() -> ComboMask {
+        ComboMask { bits: [0; WORDS] }
+    }
+
+    /// Every single one of the 1326 combos.
+    pub fn full() -> ComboMask {
+        let mut mask = ComboMask::empty();
+        for index in 0..COMBO_COUNT {
+            mask.insert(index);
+        }
+        mask
+    }
+
+    pub fn from_components(components: &BTreeSet<RangeComponent>) -> ComboMask {
+        let mut mask = ComboMask::empty();
+        for component in components.iter() {
+            for (a, b) in component.combos() {
+                mask.insert(combo_index(&a, &b));
+            }
+        }
+        mask
+    }
+
+    /// The components of the set: pairs, suited and unsuited components where all of
+    /// their combos are there, single combos otherwise.
+    pub fn components(&self) -> BTreeSet<RangeComponent> {
+        let mut components = BTreeSet::new();
+        for class in all_classes() {
+            let combos = class.combos();
+            let included : Vec<&(Card, Card)> = combos.iter()
+                .filter(|&&(a, b)| self.contains(combo_index(&a, &b)))
+                .collect();
+
+            if included.len() == combos.len() {
+                components.insert(class);
+            } else {
+                for &&(a, b) in included.iter() {
+                    components.insert(RangeComponent::combo(a, b));
+                }
+            }
+        }
+        components
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        self.bits[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&word| word == 0)
+    }
+
+    pub fn union(&self, other: &ComboMask) -> ComboMask {
+        self.combine(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &ComboMask) -> ComboMask {
+        self.combine(other, |a, b| a & b)
+    }
+
+    pub fn complement(&self) -> ComboMask {
+        ComboMask::full().difference(self)
+    }
+
+    pub fn difference(&self, other: &ComboMask) -> ComboMask {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    pub fn is_subset(&self, other: &ComboMask) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    fn combine<F: Fn(u64, u64) -> u64>(&self, other: &ComboMask, f: F) -> ComboMask {
+        let mut bits = [0; WORDS];
+        for (i, word) in bits.iter_mut().enumerate() {
+            *word = f(self.bits[i], other.bits[i]);
+        }
+        ComboMask { bits }
+    }
+
+    /// Indices of all combos in the set, in ascending order.
+    pub fn indices(&self) -> Vec<usize> {
+        (0..COMBO_COUNT).filter(|&i| self.contains(i)).collect()
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+use cards::card::Value;
+
+use super::{Range, SimpleRange};
+use super::internal::RangeComponent;
+use super::mask::{ComboMask, combo_index};
+use super::chart::RangeChart;
+use super::utils::{card_value_from_index, card_value_to_char};
+
+/// Number of rows and columns, one for each rank.
+pub const MATRIX_SIZE : usize = 13;
+
+const ANSI_FULL : &str = "\x1b[30;42m";
+const ANSI_PARTIAL : &str = "\x1b[30;43m";
+const ANSI_RESET : &str = "\x1b[0m";
+
+/// The usual 13x13 grid of a range, aces in the first row and column. Pairs are on the
+/// diagonal, suited hands above it and offsuit hands below it, so AKs is at (0, 1) and
+/// AKo at (1, 0). Every cell has a weight between 0 and 1, the share of its combos which
+/// are in the range, or their average weight for weighted ranges.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RangeMatrix {
+    weights: [[f64; MATRIX_SIZE]; MATRIX_SIZE],
+}
+
+impl RangeMatrix {
+    /// A matrix without any hands.
+    pub fn new() -> RangeMatrix {
+        RangeMatrix::default()
+    }
+
+    pub fn from_range<R: Range + ?Sized>(range: &R) -> RangeMatrix {
+        let mut matrix = RangeMatrix::new();
+        for row in 0..MATRIX_SIZE {
+            for col in 0..MATRIX_SIZE {
+                let combos = cell_class(row, col).combos();
+                let weight : f64 = combos.iter().map(|&(a, b)| range.weight_of((&a, &b))).sum();
+                matrix.weights[row][col] = weight / combos.len() as f64;
+            }
+        }
+        matrix
+    }
+
+    /// Every cell with a weight above 0 becomes part of the range with all of its combos. The
+    /// matrix does not know which combos of a cell were in a range, so "AsKh" comes back as AKo.
+    pub fn to_simple_range_lossy(&self) -> SimpleRange {
+        let mut mask = ComboMask::empty();
+        for row in 0..MATRIX_SIZE {
+            for col in 0..MATRIX_SIZE {
+                if self.weights[row][col] > 0.0 {
+                    for (a, b) in cell_class(row, col).combos() {
+                        mask.insert(combo_index(&a, &b));
+                    }
+                }
+            }
+        }
+        SimpleRange::from_computed_mask(mask)
+    }
+
+    /// The weight of a cell. Panics if the row or column is not below 13.
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        self.weights[row][col]
+    }
+
+    /// Sets the weight of a cell, it is kept between 0 and 1 and NaN or infinite weights are 0.
+    /// Panics if the row or column is not below 13.
+    pub fn set(&mut self, row: usize, col: usize, weight: f64) {
+        self.weights[row][col] = if weight.is_finite() { weight.clamp(0.0, 1.0) } else { 0.0 };
+    }
+
+    /// The hand of a cell, like "AKs", "AKo" or "AA". Panics if the row or column is not below 13.
+    pub fn cell_name(row: usize, col: usize) -> String {
+        let (high, low) = (row.min(col), row.max(col));
+        let mut name = String::new();
+        name.push(card_value_to_char(rank_of(high)));
+        name.push(card_value_to_char(rank_of(low)));
+        if row < col {
+            name.push('s');
+        } else if row > col {
+            name.push('o');
+        }
+        name
+    }
+
+    /// The grid as plain text, one row per line. Each cell is followed by '#' if all
+    /// of its combos are in the range and '+' if only some of them are, like "AKs#".
+    pub fn render_ascii(&self) -> String {
+        self.render(|name, weight| {
+            let marker = if weight >= 1.0 { '#' } else if weight > 0.0 { '+' } else { ' ' };
+            format!("{:<3}{}", name, marker)
+        })
+    }
+
+    /// The grid for terminals which understand ANSI colors: hands which are completely in the
+    /// range are green, partly included hands yellow, and both are marked like "AKs ■".
+    pub fn render_colored(&self) -> String {
+        self.render(|name, weight| {
+            if weight >= 1.0 {
+                format!("{}{:<3} ■{}", ANSI_FULL, name, ANSI_RESET)
+            } else if weight > 0.0 {
+                format!("{}{:<3} □{}", ANSI_PARTIAL, name, ANSI_RESET)
+            } else {
+                format!("{:<3}  ", name)
+            }
+        })
+    }
+
+    /// A standalone SVG chart of the range, see RangeChart for more than one action.
+    pub fn to_svg(&self) -> String {
+        RangeChart::from_matrix(self).to_svg()
+    }
+
+    /// An HTML table of the range, see RangeChart for more than one action.
+    pub fn to_html(&self) -> String {
+        RangeChart::from_matrix(self).to_html()
+    }
+
+    fn render<F: Fn(&str, f64) -> String>(&self, cell: F) -> String {
+        let mut lines = Vec::with_capacity(MATRIX_SIZE);
+        for row in 0..MATRIX_SIZE {
+            let cells : Vec<String> = (0..MATRIX_SIZE)
+                .map(|col| cell(&RangeMatrix::cell_name(row, col), self.weights[row][col]))
+                .collect();
+            lines.push(cells.join(" "));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Writes the plain text grid.
+impl fmt::Display for RangeMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render_ascii())
+    }
+}
+
+impl From<&SimpleRange> for RangeMatrix {
+    fn from(range: &SimpleRange) -> RangeMatrix {
+        RangeMatrix::from_range(range)
+    }
+}
+
+/// The rank of a row or column, aces first.
+fn rank_of(index: usize) -> Value {
+    //there is a rank for each of the 13 rows
+    card_value_from_index(MATRIX_SIZE - 1 - index).unwrap()
+}
+
+/// The pair, suited or unsuited hand of a cell.
+pub fn cell_class(row: usize, col: usize) -> RangeComponent {
+    let (high, low) = (rank_of(row.min(col)), rank_of(row.max(col)));
+    if row == col {
+        RangeComponent::Pair(high)
+    } else if row < col {
+        RangeComponent::CardsSuited(high, low)
+    } else {
+        RangeComponent::CardsUnsuited(high, low)
+    }
+}
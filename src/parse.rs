@@ -1,74 +1,404 @@
 use std::collections::BTreeSet;
 use std::cmp::{min, max};
-use std::io::{Error, ErrorKind};
 
-use cards::card::{Value};
+use cards::card::{Card, Value, Suit};
 
+use super::error::RangeParseError;
 use super::internal::RangeComponent;
-use super::utils::{is_suited, is_unsuited, is_plus};
-use super::utils::{card_value_from_char, CardValueIterator};
+use super::ranking::HandRanking;
+use super::utils::{is_suited, is_unsuited, is_plus, is_wildcard, is_separator, is_continuation, is_negation};
+use super::utils::{card_value_from_char, normalize_rank_char, CardValueIterator};
+use super::utils::{card_value_index, card_value_from_index, suit_from_char, is_suit_variable, is_suit_pattern, is_any_suit};
 
 //TODO: is there something simpler?
-pub type TreeOrError = Result<BTreeSet<RangeComponent>, Error>;
+pub type TreeOrError = Result<BTreeSet<RangeComponent>, RangeParseError>;
+
+/// A character of a token together with its byte offset in the input string.
+pub type IndexedChar = (usize, char);
+
+/// A single range component as it appeared in the input string.
+pub struct Token<'a> {
+    /// The component as written, including whitespace inside of it.
+    pub text: &'a str,
+    /// Byte offset of the component in the input string.
+    pub offset: usize,
+    /// Normalized characters of the component, without whitespace and negation.
+    pub chars: Vec<IndexedChar>,
+    /// Whether the component is removed from the range instead of added, like "!55".
+    pub negated: bool,
+    /// Weight of the component, like the 0.5 in "KK:0.5" or "[50]KK[/50]".
+    pub weight: Option<f64>,
+}
 
-pub fn parse_5_chars(chars: Vec<char>) -> TreeOrError {
-    if chars[2] != '-' {
-        //TODO: is there something simpler?
-        return Err(Error::new(ErrorKind::Other,
-            format!("No dash in expected pattern '{:?}'", chars)));
+impl<'a> Token<'a> {
+    /// The original character at the position of an indexed char.
+    fn original(&self, c: IndexedChar) -> String {
+        self.text[c.0 - self.offset..].chars().next().map(|x| x.to_string()).unwrap_or_default()
     }
 
-    if chars[0] != chars[1] || chars[3] != chars[4] {
-        return Err(Error::new(ErrorKind::Other,
-            format!("Not a range of pairs '{:?}'", chars)));
+    /// Looks up the rank of a character, pointing the error at the exact character.
+    fn rank(&self, c: IndexedChar) -> Result<Value, RangeParseError> {
+        card_value_from_char(c.1).ok_or_else(|| RangeParseError::UnknownRank {
+            token: self.original(c),
+            offset: c.0,
+        })
     }
 
-    let value_a = card_value_from_char(chars[0]);
-    let value_b = card_value_from_char(chars[3]);
+    fn bad_modifier(&self, c: IndexedChar) -> RangeParseError {
+        RangeParseError::BadModifier {
+            token: self.original(c),
+            offset: c.0,
+        }
+    }
+}
 
-    let value_greater = max(value_a, value_b);
-    let value_lesser = min(value_a, value_b);
+/// Splits a range string into components. Components are separated by ',', ';' or whitespace,
+/// but whitespace in front of modifiers and around dashes is ignored, so "KQ o+" is "KQo+".
+/// A leading '!' or '~' negates a component, so does a '-' right after ',' or ';', or after
+/// whitespace when a rank follows it directly: "A2s+ -A5s" removes A5s, while the dash in
+/// "AA - KK" or "AA -  KK" still spans the range AA-KK.
+/// Weights are either attached to a component ("KK:0.5", "AKs:75%") or apply to a
+/// bracket group of them ("[50]A5s-A2s, KQs[/50]").
+pub fn tokenize<'a>(ranges: &'a str) -> Result<Vec<Token<'a>>, RangeParseError> {
+    let mut tokens : Vec<Token> = Vec::new();
+    let mut group_offset = 0;
+    //weight of an open bracket group and the offset of its tag
+    let mut bracket : Option<(f64, usize)> = None;
+
+    for group in ranges.split(is_separator) {
+        let mut group_tokens : Vec<Token> = Vec::new();
+        let mut only_tags = false;
+
+        for (fragment_offset, fragment) in fragments(group) {
+            let offset = group_offset + fragment_offset;
+            let mut chars : Vec<IndexedChar> = fragment.char_indices()
+                .map(|(i, c)| (offset + i, normalize_rank_char(c)))
+                .collect();
+
+            if let Some(opening) = take_opening_tag(ranges, &mut chars)? {
+                if bracket.is_some() {
+                    return Err(weight_error(ranges, offset));
+                }
+                bracket = Some(opening);
+            }
+            let closing = take_closing_tag(ranges, &mut chars)?;
+
+            if chars.is_empty() {
+                only_tags = true;
+            } else {
+                //a dash glued to a rank starts a new, negated component
+                let dash_negation = chars[0].1 == '-' &&
+                    chars.get(1).map(|c| card_value_from_char(c.1).is_some() || is_wildcard(c.1)) == Some(true);
+                let continues = match group_tokens.last() {
+                    Some(previous) => previous.chars.is_empty() || (is_continuation(chars[0].1) && !dash_negation) ||
+                        previous.chars.last().map(|c| c.1 == '-' || c.1 == ':') == Some(true),
+                    None => false,
+                };
+
+                let end = chars.last().map(|c| c.0 + original_len(ranges, c.0)).unwrap();
+                if continues {
+                    let previous = group_tokens.last_mut().unwrap();
+                    previous.text = &ranges[previous.offset..end];
+                    previous.chars.extend(chars);
+                } else {
+                    let start = chars[0].0;
+                    let negated = is_negation(chars[0].1) || (chars[0].1 == '-' && (group_tokens.is_empty() || dash_negation));
+                    let chars = if negated { chars[1..].to_vec() } else { chars };
+                    let weight = bracket.map(|b| b.0);
+                    group_tokens.push(Token { text: &ranges[start..end], offset: start, chars, negated, weight });
+                }
+            }
 
-    let mut components = BTreeSet::new();
+            if let Some((weight, tag_offset)) = closing {
+                match bracket {
+                    Some((open_weight, _)) if open_weight == weight => { bracket = None; },
+                    _ => { return Err(weight_error(ranges, tag_offset)); },
+                }
+            }
+        }
+
+        if group_tokens.is_empty() && !only_tags {
+            return Err(RangeParseError::EmptyComponent {
+                token: String::new(), offset: group_offset });
+        }
 
-    for value in CardValueIterator::new(value_lesser, value_greater) {
-        components.insert(RangeComponent::Pair(value));
+        tokens.extend(group_tokens);
+        group_offset += group.len() + 1;
     }
-    
-    Ok(components)
+
+    if let Some((_, tag_offset)) = bracket {
+        return Err(weight_error(ranges, tag_offset));
+    }
+
+    for token in tokens.iter_mut() {
+        take_component_weight(ranges, token)?;
+    }
+
+    Ok(tokens)
+}
+
+/// Byte length of the original character at an offset.
+fn original_len(ranges: &str, offset: usize) -> usize {
+    ranges[offset..].chars().next().map(|c| c.len_utf8()).unwrap_or(0)
 }
 
-pub fn parse_4_chars(chars: Vec<char>) -> TreeOrError {
-    let suited = is_suited(chars[2]);
-    let unsuited = is_unsuited(chars[2]);
-    let plus = is_plus(chars[3]);
+/// Points at the whole bracket tag or weight starting at the offset.
+fn weight_error(ranges: &str, offset: usize) -> RangeParseError {
+    let rest = &ranges[offset..];
+    let end = rest.find(|c: char| c == ']' || c.is_whitespace() || is_separator(c))
+        .map(|i| if rest[i..].starts_with(']') { i + 1 } else { i })
+        .unwrap_or(rest.len());
+    RangeParseError::BadWeight { token: rest[..end].to_string(), offset }
+}
 
-    if !plus {
-        return Err(Error::new(ErrorKind::Other,
-            format!("Something is awry with this thing - no plus '{:?}'", chars)));
+/// Reads a weight like "0.5", or "50" and "50%" if it is a percentage.
+fn parse_weight(ranges: &str, chars: &[IndexedChar], percent: bool, offset: usize) -> Result<f64, RangeParseError> {
+    let mut text : String = chars.iter().map(|c| c.1).collect();
+    let percent = if text.ends_with('%') { text.pop(); true } else { percent };
+
+    if text.is_empty() || !text.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Err(weight_error(ranges, offset));
+    }
+
+    let weight = match text.parse::<f64>() {
+        Ok(w) if percent => w / 100.0,
+        Ok(w) => w,
+        Err(_) => { return Err(weight_error(ranges, offset)); },
+    };
+
+    if !(0.0..=1.0).contains(&weight) {
+        return Err(weight_error(ranges, offset));
+    }
+    Ok(weight)
+}
+
+/// Removes a leading "[50]" from the characters of a fragment and returns its weight.
+fn take_opening_tag(ranges: &str, chars: &mut Vec<IndexedChar>) -> Result<Option<(f64, usize)>, RangeParseError> {
+    let is_tag = chars.len() >= 2 && chars[0].1 == '[' && (chars[1].1.is_ascii_digit() || chars[1].1 == '.');
+    if !is_tag {
+        return Ok(None);
     }
 
+    let offset = chars[0].0;
+    let end = match chars.iter().position(|c| c.1 == ']') {
+        Some(end) => end,
+        None => { return Err(weight_error(ranges, offset)); },
+    };
+
+    let weight = parse_weight(ranges, &chars[1..end], true, offset)?;
+    chars.drain(..end + 1);
+    Ok(Some((weight, offset)))
+}
+
+/// Removes a trailing "[/50]" from the characters of a fragment and returns its weight.
+fn take_closing_tag(ranges: &str, chars: &mut Vec<IndexedChar>) -> Result<Option<(f64, usize)>, RangeParseError> {
+    let start = match (1..chars.len()).find(|&i| chars[i - 1].1 == '[' && chars[i].1 == '/') {
+        Some(i) => i - 1,
+        None => { return Ok(None); },
+    };
+
+    let offset = chars[start].0;
+    if chars.last().map(|c| c.1) != Some(']') {
+        return Err(weight_error(ranges, offset));
+    }
+
+    let weight = parse_weight(ranges, &chars[start + 2..chars.len() - 1], true, offset)?;
+    chars.truncate(start);
+    Ok(Some((weight, offset)))
+}
+
+/// Moves a weight like the ":0.5" in "KK:0.5" from the characters of a token to its weight.
+fn take_component_weight(ranges: &str, token: &mut Token) -> Result<(), RangeParseError> {
+    let colon = match token.chars.iter().position(|c| c.1 == ':') {
+        Some(colon) => colon,
+        None => { return Ok(()); },
+    };
+
+    let offset = token.chars[colon].0;
+    let weight = parse_weight(ranges, &token.chars[colon + 1..], false, offset)?;
+    token.chars.truncate(colon);
+    token.weight = Some(weight);
+    Ok(())
+}
+
+/// The whitespace separated parts of a string and their byte offsets.
+fn fragments(group: &str) -> Vec<(usize, &str)> {
+    let mut fragments = Vec::new();
+    let mut start = None;
+
+    for (i, c) in group.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => { fragments.push((s, &group[s..i])); start = None; },
+            (false, None) => { start = Some(i); },
+            _ => {},
+        }
+    }
+    if let Some(s) = start {
+        fragments.push((s, &group[s..]));
+    }
+
+    fragments
+}
+
+pub fn parse_component(token: &Token) -> TreeOrError {
+    //dispatch on characters, not bytes, so multi-byte input ends up in a proper error
+    let chars = token.chars.clone();
+
+    if chars.last().map(|c| c.1) == Some('%') {
+        return parse_top_percent(chars, token);
+    }
+
+    //the second character is a rank in "AKs+", but a suit in "AhKh" or "A[sh]Kx". Longer
+    //components have no wildcard rank there, so "AXKX" is a combo with a bad suit
+    if chars.len() >= 4 && (is_suit_pattern(chars[1].1) || is_wildcard(chars[1].1)) {
+        return parse_combo(chars, token);
+    }
+
+    match chars.len() {
+        7 => parse_7_chars(chars, token),
+        5 => parse_5_chars(chars, token),
+        4 => parse_4_chars(chars, token),
+        3 => parse_3_chars(chars, token),
+        2 => parse_2_chars(chars, token),
+        _ => Err(RangeParseError::UnrecognizedComponent {
+            token: token.text.to_string(), offset: token.offset }),
+    }
+}
+
+/// The best hands of the default ranking which make up a share of all combos, like "15%".
+pub fn parse_top_percent(chars: Vec<IndexedChar>, token: &Token) -> TreeOrError {
+    let text : String = chars[..chars.len() - 1].iter().map(|c| c.1).collect();
+
+    let percent = match text.parse::<f64>() {
+        Ok(p) if text.chars().all(|c| c.is_ascii_digit() || c == '.') && (0.0..=100.0).contains(&p) => p,
+        _ => { return Err(RangeParseError::UnrecognizedComponent {
+            token: token.text.to_string(), offset: token.offset }); },
+    };
+
+    Ok(HandRanking::default().top_classes(percent))
+}
+
+pub fn parse_7_chars(chars: Vec<IndexedChar>, token: &Token) -> TreeOrError {
+    if chars[3].1 != '-' {
+        return Err(RangeParseError::BadDashRange {
+            token: token.text.to_string(), offset: token.offset });
+    }
+
+    let first = (token.rank(chars[0])?, token.rank(chars[1])?);
+    let second = (token.rank(chars[4])?, token.rank(chars[5])?);
+
+    let suited = is_suited(chars[2].1);
+    let unsuited = is_unsuited(chars[2].1);
+
     if !suited && !unsuited {
-        return Err(Error::new(ErrorKind::Other,
-            format!("Something is awry with this thing '{:?}'", chars)));
+        return Err(token.bad_modifier(chars[2]));
+    }
+
+    //both ends need the same modifier, "A2s-A5o" is not a thing
+    if chars[6].1 != chars[2].1 {
+        return if is_suited(chars[6].1) || is_unsuited(chars[6].1) {
+            Err(RangeParseError::BadDashRange {
+                token: token.text.to_string(), offset: token.offset })
+        } else {
+            Err(token.bad_modifier(chars[6]))
+        };
     }
 
+    if first.0 == first.1 || second.0 == second.1 {
+        return Err(RangeParseError::InvalidPairModifier {
+            token: token.text.to_string(), offset: token.offset });
+    }
+
+    parse_dash_range(first, second, suited, unsuited, token)
+}
+
+pub fn parse_5_chars(chars: Vec<IndexedChar>, token: &Token) -> TreeOrError {
+    if chars[2].1 != '-' {
+        return Err(RangeParseError::BadDashRange {
+            token: token.text.to_string(), offset: token.offset });
+    }
+
+    let first = (token.rank(chars[0])?, token.rank(chars[1])?);
+    let second = (token.rank(chars[3])?, token.rank(chars[4])?);
+
+    parse_dash_range(first, second, true, true, token)
+}
+
+/// Expands a dash range between two hands of the same shape. Pairs ("22-55") and
+/// hands with the same high card ("A2s-A5s") move along the kicker, other hands
+/// with the same gap between their ranks ("54s-98s") move both ranks together.
+fn parse_dash_range(first: (Value, Value), second: (Value, Value), suited: bool, unsuited: bool, token: &Token) -> TreeOrError {
+    let bad_dash_range = || RangeParseError::BadDashRange {
+        token: token.text.to_string(), offset: token.offset };
+
+    let (high_a, low_a) = (max(first.0, first.1), min(first.0, first.1));
+    let (high_b, low_b) = (max(second.0, second.1), min(second.0, second.1));
+
     let mut components = BTreeSet::new();
 
-    let first_char = chars[0];
-    let second_char = chars[1];
+    if (high_a == low_a) != (high_b == low_b) {
+        return Err(bad_dash_range());
+    }
 
-    if first_char == second_char {
-        return Err(Error::new(ErrorKind::Other,
-            "This does not make any sense (JJs+ or similar)"));
+    if high_a == low_a {
+        for value in CardValueIterator::new(min(high_a, high_b), max(high_a, high_b)) {
+            components.insert(RangeComponent::Pair(value));
+        }
+    } else if high_a == high_b {
+        for value in CardValueIterator::new(min(low_a, low_b), max(low_a, low_b)) {
+            insert_cards(&mut components, high_a, value, suited, unsuited);
+        }
+    } else {
+        let gap = card_value_index(high_a) - card_value_index(low_a);
+        if gap != card_value_index(high_b) - card_value_index(low_b) {
+            return Err(bad_dash_range());
+        }
+
+        for value in CardValueIterator::new(min(low_a, low_b), max(low_a, low_b)) {
+            //can not fail, the greater end of the range has a valid high card
+            let high = card_value_from_index(card_value_index(value) + gap).unwrap();
+            insert_cards(&mut components, high, value, suited, unsuited);
+        }
+    }
+
+    Ok(components)
+}
+
+fn insert_cards(components: &mut BTreeSet<RangeComponent>, value_greater: Value, value_lesser: Value, suited: bool, unsuited: bool) {
+    if suited {
+        components.insert(RangeComponent::CardsSuited(value_greater, value_lesser));
+    }
+    if unsuited {
+        components.insert(RangeComponent::CardsUnsuited(value_greater, value_lesser));
+    }
+}
+
+pub fn parse_4_chars(chars: Vec<IndexedChar>, token: &Token) -> TreeOrError {
+    let suited = is_suited(chars[2].1);
+    let unsuited = is_unsuited(chars[2].1);
+    let plus = is_plus(chars[3].1);
+
+    let value_a = token.rank(chars[0])?;
+    let value_b = token.rank(chars[1])?;
+
+    if !suited && !unsuited {
+        return Err(token.bad_modifier(chars[2]));
     }
 
-    let value_a = card_value_from_char(first_char);
-    let value_b = card_value_from_char(second_char);
+    if !plus {
+        return Err(token.bad_modifier(chars[3]));
+    }
+
+    if value_a == value_b {
+        return Err(RangeParseError::InvalidPairModifier {
+            token: token.text.to_string(), offset: token.offset });
+    }
 
     let value_greater = max(value_a, value_b);
     let value_lesser = min(value_a, value_b);
 
+    let mut components = BTreeSet::new();
+
     //TODO: putdis in an other function
     for val in CardValueIterator::new(value_lesser, value_greater) {
         //TODO: this is not in suited
@@ -93,19 +423,22 @@ pub fn parse_4_chars(chars: Vec<char>) -> TreeOrError {
     Ok(components)
 }
 
-pub fn parse_3_chars(chars: Vec<char>) -> TreeOrError {
-    let suited = is_suited(chars[2]);
-    let unsuited = is_unsuited(chars[2]);
-    let plus = is_plus(chars[2]);
+pub fn parse_3_chars(chars: Vec<IndexedChar>, token: &Token) -> TreeOrError {
+    if is_wildcard(chars[0].1) || is_wildcard(chars[1].1) {
+        return parse_wildcard(chars, token);
+    }
+
+    let suited = is_suited(chars[2].1);
+    let unsuited = is_unsuited(chars[2].1);
+    let plus = is_plus(chars[2].1);
+
+    let value_a = token.rank(chars[0])?;
+    let value_b = token.rank(chars[1])?;
 
     if !plus && !suited && !unsuited {
-        return Err(Error::new(ErrorKind::Other,
-            format!("Something is awry with this thing - no modifiers '{:?}'", chars)));
+        return Err(token.bad_modifier(chars[2]));
     }
 
-    let value_a = card_value_from_char(chars[0]);
-    let value_b = card_value_from_char(chars[1]);
-
     let value_greater = max(value_a, value_b);
     let value_lesser = min(value_a, value_b);
 
@@ -113,8 +446,8 @@ pub fn parse_3_chars(chars: Vec<char>) -> TreeOrError {
 
     if value_greater == value_lesser {
         if !plus {
-            return Err(Error::new(ErrorKind::Other,
-                format!("This does not make any sense (like JJs)")));
+            return Err(RangeParseError::InvalidPairModifier {
+                token: token.text.to_string(), offset: token.offset });
         }
 
         for val in CardValueIterator::new(value_greater, Value::Ace) {
@@ -137,7 +470,7 @@ pub fn parse_3_chars(chars: Vec<char>) -> TreeOrError {
             if value_greater == val {
                 break;
             }
-    
+
             //TODO: unsuited implied by suited?
             components.insert(RangeComponent::CardsSuited(
                 value_greater, val)
@@ -151,19 +484,19 @@ pub fn parse_3_chars(chars: Vec<char>) -> TreeOrError {
     Ok(components)
 }
 
-pub fn parse_2_chars(chars: Vec<char>) -> TreeOrError {
+pub fn parse_2_chars(chars: Vec<IndexedChar>, token: &Token) -> TreeOrError {
+    if is_wildcard(chars[0].1) || is_wildcard(chars[1].1) {
+        return parse_wildcard(chars, token);
+    }
+
     let mut components = BTreeSet::new();
 
-    let first_char = chars[0];
-    let second_char = chars[1];
+    let value_a = token.rank(chars[0])?;
+    let value_b = token.rank(chars[1])?;
 
-    if first_char == second_char {
-        let value = card_value_from_char(first_char);
-        components.insert(RangeComponent::Pair(value));
+    if value_a == value_b {
+        components.insert(RangeComponent::Pair(value_a));
     } else {
-        let value_a = card_value_from_char(first_char);
-        let value_b = card_value_from_char(second_char);
-
         let value_greater = max(value_a, value_b);
         let value_lesser = min(value_a, value_b);
 
@@ -178,3 +511,148 @@ pub fn parse_2_chars(chars: Vec<char>) -> TreeOrError {
     Ok(components)
 }
 
+/// Components with a wildcard kicker, like "Axs" (A2s-AKs) or "Kx" (K2-KQ). Pairs are
+/// not part of these, except for "xx", which stands for any two cards.
+pub fn parse_wildcard(chars: Vec<IndexedChar>, token: &Token) -> TreeOrError {
+    let (suited, unsuited) = match chars.get(2) {
+        None => (true, true),
+        Some(&c) if is_suited(c.1) => (true, false),
+        Some(&c) if is_unsuited(c.1) => (false, true),
+        Some(&c) => { return Err(token.bad_modifier(c)); },
+    };
+
+    let mut components = BTreeSet::new();
+
+    let (fixed, wildcard) = if is_wildcard(chars[0].1) { (chars[1], chars[0]) } else { (chars[0], chars[1]) };
+
+    if is_wildcard(fixed.1) {
+        for value_greater in CardValueIterator::new(Value::Two, Value::Ace) {
+            if suited && unsuited {
+                components.insert(RangeComponent::Pair(value_greater));
+            }
+            for value_lesser in CardValueIterator::new(Value::Two, value_greater).take_while(|&v| v != value_greater) {
+                insert_cards(&mut components, value_greater, value_lesser, suited, unsuited);
+            }
+        }
+    } else {
+        let value_greater = token.rank(fixed)?;
+        if value_greater == Value::Two {
+            //nothing is lower than a deuce
+            return Err(RangeParseError::UnknownRank {
+                token: token.original(wildcard), offset: wildcard.0 });
+        }
+
+        for value_lesser in CardValueIterator::new(Value::Two, value_greater).take_while(|&v| v != value_greater) {
+            insert_cards(&mut components, value_greater, value_lesser, suited, unsuited);
+        }
+    }
+
+    Ok(components)
+}
+
+/// The suits a card of a combo pattern can have.
+enum SuitPattern {
+    Suits(Vec<Suit>),
+    Variable(char),
+}
+
+impl SuitPattern {
+    fn suits(&self) -> Vec<Suit> {
+        match *self {
+            SuitPattern::Suits(ref suits) => suits.clone(),
+            SuitPattern::Variable(_) => vec![Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs],
+        }
+    }
+}
+
+/// Reads a rank (or a wildcard for any rank) and a suit pattern, starting at the position.
+fn parse_card_pattern(chars: &[IndexedChar], position: &mut usize, token: &Token) -> Result<(Vec<Value>, SuitPattern), RangeParseError> {
+    let unrecognized = || RangeParseError::UnrecognizedComponent {
+        token: token.text.to_string(), offset: token.offset };
+
+    let rank_char = *chars.get(*position).ok_or_else(&unrecognized)?;
+    let values = if is_wildcard(rank_char.1) {
+        CardValueIterator::new(Value::Two, Value::Ace).collect()
+    } else {
+        vec![token.rank(rank_char)?]
+    };
+
+    let suit_char = *chars.get(*position + 1).ok_or_else(&unrecognized)?;
+    *position += 2;
+
+    let pattern = if is_suit_variable(suit_char.1) {
+        SuitPattern::Variable(suit_char.1)
+    } else if is_any_suit(suit_char.1) {
+        SuitPattern::Suits(vec![Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs])
+    } else if let Some(suit) = suit_from_char(suit_char.1) {
+        SuitPattern::Suits(vec![suit])
+    } else if suit_char.1 == '[' {
+        let mut suits = Vec::new();
+        loop {
+            let c = *chars.get(*position).ok_or_else(&unrecognized)?;
+            *position += 1;
+
+            if c.1 == ']' {
+                break;
+            }
+            suits.push(suit_from_char(c.1).ok_or_else(|| token.bad_modifier(c))?);
+        }
+
+        if suits.is_empty() {
+            return Err(unrecognized());
+        }
+        SuitPattern::Suits(suits)
+    } else {
+        return Err(token.bad_modifier(suit_char));
+    };
+
+    Ok((values, pattern))
+}
+
+/// Combos with suits, like "AhKh" or "7c7d", and patterns of them. A suit can be
+/// a variable (w, x, y or z), the same variable stands for the same suit and different
+/// variables for different suits, so "AxKx" is AKs and "AxKy" is AKo. A variable may
+/// still take the suit of a concrete card, "KhKx" is KK with a heart. A set of suits
+/// goes in brackets, "8[shd]8[shd]" is 88 without clubs, and '*' is any suit, "A*K*" is AK.
+/// An 'x' as rank stands for any rank, "AsXs" is any ace of spades with another spade. An
+/// upper case 'X' is only a rank, "AXKX" is an error.
+pub fn parse_combo(chars: Vec<IndexedChar>, token: &Token) -> TreeOrError {
+    let mut position = 0;
+    let (values_a, pattern_a) = parse_card_pattern(&chars, &mut position, token)?;
+    let (values_b, pattern_b) = parse_card_pattern(&chars, &mut position, token)?;
+
+    if let Some(&c) = chars.get(position) {
+        return Err(token.bad_modifier(c));
+    }
+
+    let mut components = BTreeSet::new();
+
+    for &suit_a in pattern_a.suits().iter() {
+        for &suit_b in pattern_b.suits().iter() {
+            if let (&SuitPattern::Variable(x), &SuitPattern::Variable(y)) = (&pattern_a, &pattern_b) {
+                if (x == y) != (suit_a == suit_b) {
+                    continue;
+                }
+            }
+
+            for &value_a in values_a.iter() {
+                for &value_b in values_b.iter() {
+                    let card_a = Card::new(value_a, suit_a);
+                    let card_b = Card::new(value_b, suit_b);
+
+                    if card_a != card_b {
+                        components.insert(RangeComponent::combo(card_a, card_b));
+                    }
+                }
+            }
+        }
+    }
+
+    //only happens if both cards are always the same
+    if components.is_empty() {
+        return Err(RangeParseError::DuplicateCard {
+            token: token.text.to_string(), offset: token.offset });
+    }
+
+    Ok(components)
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use super::error::RangeParseError;
+use super::internal::RangeComponent;
+use super::mask::COMBO_COUNT;
+use super::parse::{tokenize, parse_component};
+
+/// The order of PokerStove's preflop hand ranking: all-in equity against three random hands.
+const POKERSTOVE : &str = "\
+    AA,KK,QQ,JJ,TT,AKs,99,AQs,AKo,AJs,KQs,88,ATs,KJs,AQo,QJs,KTs,AJo,KQo,77,QTs,A9s,ATo,JTs,\
+    KJo,A8s,K9s,QJo,A7s,KTo,Q9s,A5s,66,QTo,A6s,J9s,T9s,A9o,A4s,K8s,JTo,A3s,A8o,K7s,Q8s,A2s,\
+    K9o,K6s,J8s,55,T8s,A7o,Q9o,98s,K5s,A5o,J9o,Q7s,T9o,K4s,A6o,K8o,A4o,J7s,Q6s,T7s,K3s,87s,\
+    97s,Q5s,A3o,K7o,Q8o,K2s,44,Q4s,J8o,A2o,J6s,T8o,K6o,76s,T6s,98o,86s,Q3s,96s,J5s,K5o,Q2s,\
+    J4s,33,Q7o,65s,K4o,J7o,75s,J3s,T5s,T7o,85s,95s,Q6o,97o,87o,K3o,J2s,T4s,54s,Q5o,T3s,K2o,\
+    64s,22,74s,Q4o,84s,T2s,76o,94s,J6o,53s,T6o,86o,96o,93s,Q3o,J5o,63s,43s,92s,Q2o,73s,65o,\
+    J4o,83s,75o,52s,82s,85o,T5o,J3o,95o,54o,62s,42s,J2o,T4o,72s,64o,32s,T3o,74o,84o,T2o,94o,\
+    53o,93o,63o,43o,92o,73o,83o,52o,82o,42o,62o,72o,32o";
+
+/// Sorted by Sklansky-Chubukov number: the largest stack, in big blinds, with which moving
+/// all-in from the small blind still breaks even against an opponent who knows the hand.
+const SKLANSKY_CHUBUKOV : &str = "\
+    AA,KK,AKs,QQ,AKo,JJ,AQs,TT,AQo,99,AJs,88,ATs,AJo,77,66,ATo,A9s,55,A8s,KQs,44,A9o,A7s,KJs,\
+    A5s,A8o,A6s,A4s,33,A3s,KTs,A7o,KQo,A2s,A5o,A6o,A4o,KJo,QJs,K9s,A3o,22,A2o,KTo,QTs,K8s,K7s,\
+    JTs,K9o,K6s,QJo,K5s,Q9s,K8o,K4s,QTo,K7o,K3s,K6o,K2s,Q8s,J9s,K5o,Q9o,JTo,K4o,Q7s,T9s,Q6s,\
+    K3o,J8s,Q5s,K2o,Q8o,Q4s,Q3s,J9o,T8s,J7s,Q7o,Q2s,Q6o,98s,Q5o,J8o,T9o,J6s,T7s,J5s,Q4o,J4s,\
+    J7o,Q3o,97s,T8o,T6s,J3s,Q2o,J2s,87s,J6o,98o,T7o,96s,T5s,J5o,T4s,J4o,86s,97o,T6o,T3s,76s,\
+    95s,J3o,T2s,87o,85s,96o,T5o,J2o,94s,75s,65s,T4o,86o,93s,84s,95o,T3o,76o,92s,74s,85o,T2o,\
+    54s,64s,83s,94o,75o,82s,73s,93o,65o,53s,63s,84o,92o,43s,74o,54o,72s,64o,52s,62s,83o,42s,\
+    82o,73o,53o,63o,32s,43o,72o,52o,62o,42o,32o";
+
+/// Sorted by all-in equity against a random hand.
+const EQUITY_VS_RANDOM : &str = "\
+    AA,KK,QQ,JJ,TT,99,88,AKs,AQs,77,AJs,AKo,ATs,AQo,AJo,KQs,66,A9s,ATo,KJs,KTs,A8s,KQo,A7s,\
+    A9o,KJo,QJs,55,A6s,A5s,K9s,A8o,KTo,QTs,A4s,A7o,K8s,A3s,QJo,A6o,K9o,Q9s,JTs,A5o,K7s,A2s,44,\
+    QTo,K6s,A4o,K8o,Q8s,A3o,K5s,J9s,Q9o,K7o,JTo,A2o,K4s,Q7s,K3s,J8s,K6o,T9s,Q6s,33,Q8o,J9o,\
+    K5o,K2s,Q5s,J7s,K4o,T8s,Q4s,Q7o,J8o,T9o,K3o,Q3s,Q6o,T7s,98s,J6s,K2o,22,Q2s,Q5o,J5s,T8o,\
+    J7o,Q4o,97s,T6s,J4s,Q3o,J3s,98o,87s,J6o,T7o,J2s,96s,Q2o,J5o,T5s,T4s,97o,J4o,86s,T6o,95s,\
+    T3s,J3o,76s,87o,T2s,85s,96o,T5o,J2o,94s,T4o,75s,93s,86o,65s,84s,T3o,95o,76o,92s,74s,T2o,\
+    85o,54s,64s,83s,94o,75o,82s,93o,73s,65o,53s,63s,84o,92o,74o,43s,72s,54o,64o,52s,62s,83o,\
+    82o,42s,73o,53o,63o,32s,43o,72o,52o,62o,42o,32o";
+
+/// An order of the 169 pair, suited and unsuited hands, from the best to the worst.
+/// Used to build ranges like "the top 15% of hands".
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HandRanking {
+    classes: Vec<RangeComponent>,
+}
+
+impl HandRanking {
+    /// The ranking of PokerStove, which is the default for "15%" in range strings.
+    pub fn pokerstove() -> HandRanking {
+        //the built-in rankings are known to be valid
+        HandRanking::new_from_string(POKERSTOVE).unwrap()
+    }
+
+    /// Hands ranked by their Sklansky-Chubukov number, which favors high cards.
+    pub fn sklansky_chubukov() -> HandRanking {
+        HandRanking::new_from_string(SKLANSKY_CHUBUKOV).unwrap()
+    }
+
+    /// Hands ranked by their all-in equity against a random hand, which favors pairs.
+    pub fn equity_vs_random() -> HandRanking {
+        HandRanking::new_from_string(EQUITY_VS_RANDOM).unwrap()
+    }
+
+    /// Construct a custom ranking from a string of the kind "AA,KK,AKs,QQ", best hands first.
+    /// Every component has to be a single pair, suited or unsuited hand, and no hand may be
+    /// listed twice. Hands which are not listed are never part of a top range.
+    pub fn new_from_string(ranking: &str) -> Result<HandRanking, RangeParseError> {
+        if ranking.trim().is_empty() {
+            return Err(RangeParseError::EmptyInput {
+                token: String::new(), offset: 0 });
+        }
+
+        let mut classes = Vec::new();
+        for token in tokenize(ranking)? {
+            let components = parse_component(&token)?;
+            let class = match components.iter().next() {
+                Some(&class) if components.len() == 1 && !token.negated && token.weight.is_none() => class,
+                _ => { return Err(RangeParseError::UnrecognizedComponent {
+                    token: token.text.to_string(), offset: token.offset }); },
+            };
+
+            if let RangeComponent::Combo(_, _) = class {
+                return Err(RangeParseError::UnrecognizedComponent {
+                    token: token.text.to_string(), offset: token.offset });
+            }
+            if classes.contains(&class) {
+                return Err(RangeParseError::DuplicateHand {
+                    token: token.text.to_string(), offset: token.offset });
+            }
+            classes.push(class);
+        }
+
+        Ok(HandRanking { classes })
+    }
+
+    /// The best hands of the ranking, added one after another until they make up
+    /// at least the given percentage of all 1326 combos.
+    pub(crate) fn top_classes(&self, percent: f64) -> BTreeSet<RangeComponent> {
+        let target = percent / 100.0 * COMBO_COUNT as f64;
+        let mut count = 0;
+
+        let mut classes = BTreeSet::new();
+        for class in self.classes.iter() {
+            //a little tolerance, so 100% is every combo despite rounding
+            if count as f64 >= target - 1e-9 {
+                break;
+            }
+            count += class.combination_count();
+            classes.insert(*class);
+        }
+        classes
+    }
+
+    pub fn get_hand_count(&self) -> usize {
+        self.classes.len()
+    }
+}
+
+impl Default for HandRanking {
+    fn default() -> HandRanking {
+        HandRanking::pokerstove()
+    }
+}
+
+impl FromStr for HandRanking {
+    type Err = RangeParseError;
+
+    fn from_str(s: &str) -> Result<HandRanking, RangeParseError> {
+        HandRanking::new_from_string(s)
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+
+use cards::card::Card;
+
+use super::Range;
+use super::combos::ComboIter;
+use super::error::DrawError;
+use super::internal::RangeComponent;
+
+/// How likely the hands of a range are to be drawn.
+#[derive(Clone, Copy, Default)]
+pub enum Sampling<'a> {
+    /// Every combo is as likely as its weight, so AKo comes up three times as often as AKs.
+    #[default]
+    Combinatorial,
+    /// Every hand class, like AKs, AKo or 77, which has a combo in the range is equally likely,
+    /// no matter how many combos it has. Inside of a class, combos are as likely as their weight.
+    UniformByClass,
+    /// Every combo is as likely as its weight times the weight the function gives it.
+    /// Negative weights count as 0.
+    Custom(&'a dyn Fn((&Card, &Card)) -> f64),
+}
+
+/// Draws a combo of the range which does not use any of the dead cards.
+pub fn sample<T: Range + ?Sized, R: Rng>(range: &T, sampling: &Sampling, dead: &[Card], rng: &mut R) -> Result<(Card, Card), DrawError> {
+    let mut weighted : Vec<((Card, Card), f64)> = ComboIter::new(range, dead).collect();
+
+    match *sampling {
+        Sampling::Combinatorial => {},
+        Sampling::UniformByClass => {
+            //every class ends up with a total weight of 1
+            let mut class_weights : BTreeMap<RangeComponent, f64> = BTreeMap::new();
+            for &((a, b), weight) in weighted.iter() {
+                *class_weights.entry(RangeComponent::class_of(&a, &b)).or_insert(0.0) += weight;
+            }
+            for &mut ((a, b), ref mut weight) in weighted.iter_mut() {
+                *weight /= class_weights[&RangeComponent::class_of(&a, &b)];
+            }
+        },
+        Sampling::Custom(weight_of) => {
+            for &mut ((a, b), ref mut weight) in weighted.iter_mut() {
+                *weight *= weight_of((&a, &b)).max(0.0);
+            }
+        },
+    }
+
+    pick_weighted(&weighted, rng)
+}
+
+/// Picks one of the combos with a probability proportional to its weight.
+fn pick_weighted<R: Rng>(weighted: &[((Card, Card), f64)], rng: &mut R) -> Result<(Card, Card), DrawError> {
+    let total_weight : f64 = weighted.iter().map(|item| item.1).sum();
+    if total_weight <= 0.0 {
+        return Err(DrawError::NoCombosLeft);
+    }
+
+    let mut n : f64 = rng.gen_range(0.0, total_weight);
+    for &(combo, weight) in weighted.iter() {
+        if n < weight {
+            return Ok(combo);
+        }
+        n -= weight;
+    }
+    //rounding can leave a tiny rest, it belongs to the last combo with any weight
+    Ok(weighted.iter().rev().find(|item| item.1 > 0.0).unwrap().0)
+}
@@ -0,0 +1,41 @@
+use cards::card::Card;
+
+use super::Range;
+use super::combos::ComboIter;
+use super::mask::COMBO_COUNT;
+
+/// How many combos a range has, split by the kind of hand. Combos of weighted ranges
+/// count by their weight, so the numbers are not always whole.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ComboCounts {
+    pub pairs: f64,
+    pub suited: f64,
+    pub offsuit: f64,
+}
+
+impl ComboCounts {
+    pub fn total(&self) -> f64 {
+        self.pairs + self.suited + self.offsuit
+    }
+
+    /// The share of all 1326 combos, between 0 and 100.
+    pub fn percentage(&self) -> f64 {
+        self.total() / COMBO_COUNT as f64 * 100.0
+    }
+}
+
+/// Adds up the weights of all combos of a range which do not use any of the dead cards.
+pub fn count_combos<R: Range + ?Sized>(range: &R, dead: &[Card]) -> ComboCounts {
+    let mut counts = ComboCounts::default();
+
+    for ((a, b), weight) in ComboIter::new(range, dead) {
+        if a.value == b.value {
+            counts.pairs += weight;
+        } else if a.suit == b.suit {
+            counts.suited += weight;
+        } else {
+            counts.offsuit += weight;
+        }
+    }
+    counts
+}
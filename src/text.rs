@@ -0,0 +1,118 @@
+use std::collections::BTreeSet;
+
+use super::internal::RangeComponent;
+use super::utils::{card_value_index, card_value_from_index, card_value_to_char};
+
+/// The text of a range without any hands.
+pub const EMPTY_TEXT : &str = "none";
+
+/// Writes components in the shortest usual notation, like "TT+,A5s-A2s,KQ,AhKh". Equal sets
+/// of components always give the same text, which parses back to the same components,
+/// "none" if there are no components.
+pub fn canonical_text(components: &BTreeSet<RangeComponent>) -> String {
+    let mut parts : Vec<String> = Vec::new();
+
+    let pairs : Vec<usize> = components.iter()
+        .filter_map(|c| match *c {
+            RangeComponent::Pair(val) => Some(card_value_index(val)),
+            _ => None,
+        })
+        .collect();
+
+    for (lowest, highest) in runs(&pairs).into_iter().rev() {
+        let (low, high) = (rank_char(lowest), rank_char(highest));
+        if lowest == highest {
+            parts.push(format!("{}{}", high, high));
+        } else if highest == ACE {
+            parts.push(format!("{}{}+", low, low));
+        } else {
+            parts.push(format!("{}{}-{}{}", high, high, low, low));
+        }
+    }
+
+    for high in (1..ACE + 1).rev() {
+        let kickers = |suited: bool| -> Vec<usize> {
+            components.iter()
+                .filter_map(|c| match (*c, suited) {
+                    (RangeComponent::CardsSuited(g, l), true) |
+                    (RangeComponent::CardsUnsuited(g, l), false) if card_value_index(g) == high => Some(card_value_index(l)),
+                    _ => None,
+                })
+                .collect()
+        };
+        let suited = kickers(true);
+        let unsuited = kickers(false);
+
+        //either write suited and offsuit hands separately, or take out the ones both have in common
+        let mut separate = kicker_parts(high, &suited, "s");
+        separate.extend(kicker_parts(high, &unsuited, "o"));
+
+        let common : Vec<usize> = suited.iter().filter(|k| unsuited.contains(k)).cloned().collect();
+        let mut combined = kicker_parts(high, &common, "");
+        combined.extend(kicker_parts(high, &without(&suited, &common), "s"));
+        combined.extend(kicker_parts(high, &without(&unsuited, &common), "o"));
+
+        let length = |p: &Vec<String>| (p.len(), p.iter().map(|s| s.len()).sum::<usize>());
+        if length(&combined) < length(&separate) {
+            parts.extend(combined);
+        } else {
+            parts.extend(separate);
+        }
+    }
+
+    for component in components.iter() {
+        if let RangeComponent::Combo(a, b) = *component {
+            //pairs are written with spades before hearts, diamonds and clubs
+            if a.value == b.value {
+                parts.push(format!("{}{}", b, a));
+            } else {
+                parts.push(format!("{}{}", a, b));
+            }
+        }
+    }
+
+    if parts.is_empty() {
+        return EMPTY_TEXT.to_string();
+    }
+    parts.join(",")
+}
+
+const ACE : usize = 12;
+
+fn rank_char(index: usize) -> char {
+    //the index always comes from a value
+    card_value_to_char(card_value_from_index(index).unwrap())
+}
+
+/// Kicker runs of one high card, like "ATs+" (AT up to AK), "A5s-A2s" or "A8s".
+fn kicker_parts(high: usize, kickers: &[usize], modifier: &str) -> Vec<String> {
+    let h = rank_char(high);
+    runs(kickers).into_iter().rev()
+        .map(|(lowest, highest)| {
+            let (low, top) = (rank_char(lowest), rank_char(highest));
+            if lowest == highest {
+                format!("{}{}{}", h, low, modifier)
+            } else if highest + 1 == high {
+                format!("{}{}{}+", h, low, modifier)
+            } else {
+                format!("{}{}{}-{}{}{}", h, top, modifier, h, low, modifier)
+            }
+        })
+        .collect()
+}
+
+/// Consecutive runs in sorted indices, as lowest and highest index of each run.
+fn runs(indices: &[usize]) -> Vec<(usize, usize)> {
+    let mut runs : Vec<(usize, usize)> = Vec::new();
+    for &index in indices {
+        match runs.last_mut() {
+            Some(run) if run.1 + 1 == index => { run.1 = index; },
+            _ => { runs.push((index, index)); },
+        }
+    }
+    runs
+}
+
+fn without(values: &[usize], removed: &[usize]) -> Vec<usize> {
+    values.iter().filter(|v| !removed.contains(v)).cloned().collect()
+}
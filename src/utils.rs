@@ -1,4 +1,4 @@
-use rand::{Rng, ThreadRng};
+use rand::Rng;
 use cards::card::{Value, Suit};
 
 //TODO: this could go into the cards crate
@@ -40,27 +40,91 @@ impl Iterator for CardValueIterator {
             None => {self.finished_flag = true}
         }
 
-        return Some(returning);
+        Some(returning)
     }
 }
 
 //TODO: put this function elsewhere
-pub fn card_value_from_char(c: char) -> Value {
+pub fn card_value_from_char(c: char) -> Option<Value> {
     match c {
-        '2' => Value::Two,
-        '3' => Value::Three,
-        '4' => Value::Four,
-        '5' => Value::Five,
-        '6' => Value::Six,
-        '7' => Value::Seven,
-        '8' => Value::Eight,
-        '9' => Value::Nine,
-        'T' => Value::Ten,
-        'J' => Value::Jack,
-        'Q' => Value::Queen,
-        'K' => Value::King,
-        'A' => Value::Ace,
-        _ => panic!("Unrecognized value character")
+        '2' => Some(Value::Two),
+        '3' => Some(Value::Three),
+        '4' => Some(Value::Four),
+        '5' => Some(Value::Five),
+        '6' => Some(Value::Six),
+        '7' => Some(Value::Seven),
+        '8' => Some(Value::Eight),
+        '9' => Some(Value::Nine),
+        'T' => Some(Value::Ten),
+        'J' => Some(Value::Jack),
+        'Q' => Some(Value::Queen),
+        'K' => Some(Value::King),
+        'A' => Some(Value::Ace),
+        _ => None
+    }
+}
+
+pub fn card_value_to_char(value: Value) -> char {
+    match value {
+        Value::Two => '2',
+        Value::Three => '3',
+        Value::Four => '4',
+        Value::Five => '5',
+        Value::Six => '6',
+        Value::Seven => '7',
+        Value::Eight => '8',
+        Value::Nine => '9',
+        Value::Ten => 'T',
+        Value::Jack => 'J',
+        Value::Queen => 'Q',
+        Value::King => 'K',
+        Value::Ace => 'A',
+    }
+}
+
+/// Suits can be written as letters or symbols, "Ah" and "A♥" are the same card.
+pub fn suit_from_char(c: char) -> Option<Suit> {
+    match c {
+        's' | '♠' | '♤' => Some(Suit::Spades),
+        'h' | '♥' | '♡' => Some(Suit::Hearts),
+        'd' | '♦' | '♢' => Some(Suit::Diamonds),
+        'c' | '♣' | '♧' => Some(Suit::Clubs),
+        _ => None
+    }
+}
+
+/// Stands for some suit in a combo pattern, like in "AxKx".
+pub fn is_suit_variable(c: char) -> bool {
+    c == 'w' || c == 'x' || c == 'y' || c == 'z'
+}
+
+/// Stands for any suit in a combo pattern, "A*K*" is AK.
+pub fn is_any_suit(c: char) -> bool {
+    c == '*'
+}
+
+/// Anything that can follow the rank of a card in a combo pattern.
+pub fn is_suit_pattern(c: char) -> bool {
+    suit_from_char(c).is_some() || is_suit_variable(c) || is_any_suit(c) || c == '['
+}
+
+/// Position of a value, from 0 for Two up to 12 for Ace.
+pub fn card_value_index(value: Value) -> usize {
+    value as usize
+}
+
+pub fn card_value_from_index(index: usize) -> Option<Value> {
+    let values = [Value::Two, Value::Three, Value::Four, Value::Five, Value::Six,
+        Value::Seven, Value::Eight, Value::Nine, Value::Ten, Value::Jack,
+        Value::Queen, Value::King, Value::Ace];
+    values.get(index).cloned()
+}
+
+/// Ranks are accepted in lower case as well, "aks" is "AKs".
+pub fn normalize_rank_char(c: char) -> char {
+    match c {
+        't' | 'j' | 'q' | 'k' | 'a' => c.to_ascii_uppercase(),
+        _ => c
     }
 }
 
@@ -82,6 +146,12 @@ pub fn next_card_value(cv: &Value) -> Option<Value> {
     }
 }
 
+/// Matches any rank, where a rank is expected. In place of a suit, 'x' is a suit variable
+/// and '*' any suit, see parse_combo.
+pub fn is_wildcard(c: char) -> bool {
+    c == 'x' || c == 'X' || c == '*'
+}
+
 pub fn is_plus(c: char) -> bool {
     c == '+'
 }
@@ -94,8 +164,23 @@ pub fn is_unsuited(c: char) -> bool {
     c == 'o' ||  c == 'u'
 }
 
+/// Separates range components. Whitespace does so as well, see is_continuation.
+pub fn is_separator(c: char) -> bool {
+    c == ',' || c == ';'
+}
+
+/// Removes the following component from the range.
+pub fn is_negation(c: char) -> bool {
+    c == '!' || c == '~'
+}
+
+/// Characters which continue the previous component even after whitespace.
+pub fn is_continuation(c: char) -> bool {
+    is_suited(c) || is_unsuited(c) || is_plus(c) || c == '-' || c == ':'
+}
+
 /// Generates two distinct suits.
-pub fn gen_random_suits(rng: &mut ThreadRng) -> (Suit, Suit) {
+pub fn gen_random_suits<R: Rng>(rng: &mut R) -> (Suit, Suit) {
     let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
 
     let n1 = rng.gen_range(0,4);
@@ -0,0 +1,154 @@
+use rand::Rng;
+use std::collections::BTreeMap;
+
+use cards::card::{Card};
+
+use super::Range;
+use super::error::RangeParseError;
+use super::internal::RangeComponent;
+use super::mask::{COMBO_COUNT, combo_index};
+use super::parse::{tokenize, parse_component};
+
+/// A range where every component has a weight between 0 and 1, like "AA,KK:0.5,[75]AKs,AQs[/75]".
+#[derive(Debug, Clone)]
+pub struct WeightedRange {
+    text: String,
+    components: BTreeMap<RangeComponent, f64>,
+    // the weight of each of the 1326 combos, for constant time lookups
+    weights: Vec<f64>,
+    weight_lookup: Vec<(f64, RangeComponent)>,
+    total_weight: f64,
+}
+
+impl WeightedRange {
+    /// Construct a range from a string like the ones SimpleRange takes, but with weights. A weight
+    /// follows a component after a colon ("KK:0.5", "KK:50%") or applies to a bracket group of
+    /// them ("[50]KK,QQ[/50]"). Components without a weight have a weight of 1. If components
+    /// overlap, the one further right decides the weight.
+    pub fn new_from_string(ranges: &str) -> Result<WeightedRange, RangeParseError> {
+        if ranges.trim().is_empty() {
+            return Err(RangeParseError::EmptyInput {
+                token: String::new(), offset: 0 });
+        }
+
+        //weights are tracked per combo, so overlapping components can have different weights
+        let mut combo_weights : BTreeMap<RangeComponent, f64> = BTreeMap::new();
+
+        for token in tokenize(ranges)? {
+            let combos : Vec<RangeComponent> = parse_component(&token)?.iter()
+                .flat_map(|c| c.combos())
+                .map(|(a, b)| RangeComponent::combo(a, b))
+                .collect();
+
+            if token.negated {
+                if !combos.iter().all(|c| combo_weights.contains_key(c)) {
+                    return Err(RangeParseError::ExclusionNotInRange {
+                        token: token.text.to_string(), offset: token.offset });
+                }
+                for combo in combos.iter() {
+                    combo_weights.remove(combo);
+                }
+                continue;
+            }
+
+            let weight = token.weight.unwrap_or(1.0);
+            for combo in combos {
+                combo_weights.insert(combo, weight);
+            }
+        }
+
+        let components = group_combos(&combo_weights);
+
+        let mut weights = vec![0.0; COMBO_COUNT];
+        for (combo, &weight) in combo_weights.iter() {
+            if let RangeComponent::Combo(a, b) = *combo {
+                weights[combo_index(&a, &b)] = weight;
+            }
+        }
+
+        let mut total_weight = 0.0;
+        let mut weight_lookup : Vec<(f64, RangeComponent)> = Vec::new();
+
+        //like the combination lookup of SimpleRange, but every combination counts by its weight
+        for (component, weight) in components.iter() {
+            total_weight += component.combination_count() as f64 * weight;
+            weight_lookup.push( (total_weight, *component) );
+        }
+
+        if total_weight <= 0.0 {
+            return Err(RangeParseError::EmptyRange {
+                token: String::from(ranges), offset: 0 });
+        }
+
+        Ok(WeightedRange {
+            text: String::from(ranges),
+            components,
+            weights,
+            weight_lookup,
+            total_weight,
+        })
+    }
+
+    pub fn get_range_text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn get_component_count(&self) -> usize {
+        self.components.len()
+    }
+}
+
+/// Groups combos with the same weight back into pairs, suited and unsuited components,
+/// wherever all of their combos are there. Combos without weight are dropped.
+fn group_combos(combo_weights: &BTreeMap<RangeComponent, f64>) -> BTreeMap<RangeComponent, f64> {
+    let mut combos_per_class : BTreeMap<RangeComponent, Vec<(RangeComponent, f64)>> = BTreeMap::new();
+    for (combo, &weight) in combo_weights.iter() {
+        if let RangeComponent::Combo(a, b) = *combo {
+            if weight > 0.0 {
+                combos_per_class.entry(RangeComponent::class_of(&a, &b)).or_default().push((*combo, weight));
+            }
+        }
+    }
+
+    let mut components = BTreeMap::new();
+    for (class, combos) in combos_per_class {
+        let weight = combos[0].1;
+        if combos.len() == class.combination_count() && combos.iter().all(|c| c.1 == weight) {
+            components.insert(class, weight);
+        } else {
+            components.extend(combos);
+        }
+    }
+
+    components
+}
+
+impl Range for WeightedRange {
+    /// Checks whether a hand is in the range with any weight.
+    fn contains(&self, hand: (&Card, &Card)) -> bool {
+        self.weight_of(hand) > 0.0
+    }
+
+    /// Draw a hand from the range, weighted by their combinatoric probability times their weight.
+    fn draw_with(&self, mut rng: &mut dyn Rng) -> (Card, Card) {
+        let n: f64 = (&mut rng).gen_range(0.0, self.total_weight);
+
+        let range_component = self.weight_lookup.iter()
+            .find(|item| n < item.0)
+            .or_else(|| self.weight_lookup.last())
+            .map(|item| item.1)
+            .unwrap();
+        range_component.random_combo(&mut rng)
+    }
+
+    fn weight_of(&self, hand: (&Card, &Card)) -> f64 {
+        //the same card twice is no combo, it is only looked at as a pair, suited or unsuited hand
+        if hand.0 != hand.1 {
+            return self.weights[combo_index(hand.0, hand.1)];
+        }
+        self.components.iter()
+            .find(|&(component, _)| component.contains(hand))
+            .map(|(_, &weight)| weight)
+            .unwrap_or(0.0)
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Stops running calculations from another thread, like the one of a user interface.
+/// Clones of a handle stop the same calculations.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    // counts the cancellations, calculations stop when it changes while they run
+    generation: Arc<AtomicUsize>,
+}
+
+impl CancelHandle {
+    pub fn new() -> CancelHandle {
+        CancelHandle::default()
+    }
+
+    /// Asks the calculations which are running to stop, they do so after the boards or samples
+    /// they are working on. Calculations started afterwards are not affected.
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// The cancellation state of a calculation which starts now.
+    pub(crate) fn start(&self) -> Run {
+        Run { generation: self.generation.clone(), started: self.generation.load(Ordering::SeqCst) }
+    }
+}
+
+/// A single calculation, which is cancelled once its handle was cancelled after it started.
+pub struct Run {
+    generation: Arc<AtomicUsize>,
+    started: usize,
+}
+
+impl Run {
+    pub fn is_cancelled(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) != self.started
+    }
+}
+
+/// One thread for each core, or a single one if the number of cores is unknown.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map(|count| count.get()).unwrap_or(1)
+}
+
+/// Does the work of every chunk on up to `threads` threads. The results are in the order of the
+/// chunks, no matter which thread finished first, so they can be combined the same way every time.
+/// After each chunk, the calling thread is told how many are done. None if the work was cancelled,
+/// work which notices the cancellation itself returns None as well.
+pub fn run_chunks<T, F>(chunks: usize, threads: usize, run: &Run, progress: &dyn Fn(usize), work: F) -> Option<Vec<T>>
+    where T: Send, F: Fn(usize) -> Option<T> + Sync
+{
+    let mut results : Vec<Option<T>> = (0..chunks).map(|_| None).collect();
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, chunks.max(1)) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || {
+                //threads take the next chunk until there are none left
+                loop {
+                    let chunk = next.fetch_add(1, Ordering::SeqCst);
+                    if chunk >= chunks || run.is_cancelled() {
+                        return;
+                    }
+                    match work(chunk) {
+                        Some(result) => if sender.send((chunk, result)).is_err() { return },
+                        None => return,
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut done = 0;
+        for (chunk, result) in receiver {
+            results[chunk] = Some(result);
+            done += 1;
+            progress(done);
+        }
+    });
+
+    if run.is_cancelled() {
+        return None;
+    }
+    results.into_iter().collect()
+}
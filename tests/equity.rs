@@ -0,0 +1,292 @@
+#![allow(clippy::bool_assert_comparison)]
+
+extern crate rand;
+extern crate cards;
+extern crate pokerhandrange;
+
+use rand::{SeedableRng, XorShiftRng};
+use std::sync::{Arc, Mutex};
+use cards::card::{Card, Value, Suit};
+
+use pokerhandrange::{SimpleRange, WeightedRange, EquityError};
+use pokerhandrange::equity::{EquityCalculator, EquityResult};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn check_result(result: &EquityResult) {
+    for player in result.players.iter() {
+        assert_eq!(close(player.win + player.tie + player.lose, 1.0), true);
+    }
+    let equity : f64 = result.players.iter().map(|player| player.equity).sum();
+    assert_eq!(close(equity, 1.0), true);
+}
+
+#[test]
+fn exact_preflop_equity() {
+    let mut calculator = EquityCalculator::new();
+    calculator.add_range(&SimpleRange::new_from_string("AhAs").unwrap());
+    calculator.add_range(&SimpleRange::new_from_string("KdKc").unwrap());
+    let result = calculator.exact().unwrap();
+    check_result(&result);
+
+    //48 choose 5 boards for the single matchup
+    assert_eq!(result.showdowns, 1_712_304);
+    let (aces, kings) = (result.players[0], result.players[1]);
+    assert_eq!(close(aces.win, kings.lose) && close(aces.tie, kings.tie), true);
+    assert!(aces.equity > 0.80 && aces.equity < 0.83, "equity of aces was {}", aces.equity);
+    assert_eq!(aces.confidence_interval(), (aces.equity, aces.equity));
+}
+
+#[test]
+fn exact_equity_respects_weights() {
+    //the aces of the second player never show up, their weight is 0
+    let mut calculator = EquityCalculator::new();
+    calculator.add_range(&SimpleRange::new_from_string("KdKc").unwrap());
+    calculator.add_range(&WeightedRange::new_from_string("AhAs,QhQs,AcAd:0").unwrap());
+    let result = calculator.exact().unwrap();
+    check_result(&result);
+    assert_eq!(result.showdowns, 2 * 1_712_304);
+
+    //kings beat queens about as often as aces beat kings, so this is a coin flip
+    let kings = result.players[0];
+    assert!(kings.equity > 0.49 && kings.equity < 0.51, "equity of kings was {}", kings.equity);
+}
+
+#[test]
+fn monte_carlo_equity_is_unbiased() {
+    //AhAs only meets KcKh, 7c2d meets both hands; drawing the first player's hand on its own
+    //would show the aces in half of the showdowns instead of a third
+    let mut calculator = EquityCalculator::new();
+    calculator.add_range(&SimpleRange::new_from_string("AhAs,7c2d").unwrap());
+    calculator.add_range(&SimpleRange::new_from_string("AsKd,KcKh").unwrap());
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let result = calculator.monte_carlo(20_000, &mut rng).unwrap();
+    check_result(&result);
+    assert_eq!(result.showdowns, 20_000);
+
+    //the exact equity is 0.4216
+    let player = result.players[0];
+    assert!((player.equity - 0.4216).abs() < 4.0 * player.std_error, "equity was {} +- {}", player.equity, player.std_error);
+    assert!(player.std_error > 0.0 && player.std_error < 0.01);
+
+    let (low, high) = player.confidence_interval();
+    assert!(low < player.equity && player.equity < high);
+
+    //the same seed gives the same result
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    assert_eq!(calculator.monte_carlo(20_000, &mut rng).unwrap(), result);
+}
+
+#[test]
+fn monte_carlo_with_ranges_which_hardly_fit_together() {
+    //the second player almost always holds the same hand as the first one, the showdowns are
+    //against the queens with their tiny weight
+    let mut calculator = EquityCalculator::new();
+    calculator.add_range(&SimpleRange::new_from_string("AhKh").unwrap());
+    calculator.add_range(&WeightedRange::new_from_string("AhKh,QQ:0.000000001").unwrap());
+
+    let mut rng = XorShiftRng::from_seed([29, 30, 31, 32]);
+    let result = calculator.monte_carlo(2_000, &mut rng).unwrap();
+    check_result(&result);
+    assert_eq!(result.showdowns, 2_000);
+    let player = result.players[0];
+    assert!(player.equity > 0.40 && player.equity < 0.52, "equity against queens was {}", player.equity);
+}
+
+#[test]
+fn monte_carlo_stops_at_target_error() {
+    let mut calculator = EquityCalculator::new();
+    calculator.add_range(&SimpleRange::new_from_string("QQ+").unwrap());
+    calculator.add_range(&SimpleRange::new_from_string("AKs,AKo").unwrap());
+
+    let mut rng = XorShiftRng::from_seed([5, 6, 7, 8]);
+    let result = calculator.monte_carlo_until(0.01, 1_000_000, &mut rng).unwrap();
+    check_result(&result);
+    assert!(result.showdowns < 1_000_000);
+    assert!(result.players.iter().all(|player| player.std_error <= 0.01));
+
+    let empty = EquityCalculator::new();
+    assert_eq!(empty.monte_carlo(10, &mut rng), Err(EquityError::PlayerCount { count: 0 }));
+}
+
+#[test]
+fn multiway_equity() {
+    let mut calculator = EquityCalculator::new();
+    for range in ["AA", "KK", "QQ"].iter() {
+        calculator.add_range(&SimpleRange::new_from_string(range).unwrap());
+    }
+
+    let mut rng = XorShiftRng::from_seed([9, 10, 11, 12]);
+    let result = calculator.calculate(10_000, &mut rng).unwrap();
+    check_result(&result);
+    assert_eq!(result.players.len(), 3);
+    assert_eq!(result.showdowns, 10_000);
+
+    //aces win about two thirds, the others split the rest
+    let equities : Vec<f64> = result.players.iter().map(|player| player.equity).collect();
+    assert!(equities[0] > 0.62 && equities[0] < 0.70, "equities were {:?}", equities);
+    assert!(equities[1] > 0.15 && equities[1] < 0.22, "equities were {:?}", equities);
+    assert!(equities[2] > 0.13 && equities[2] < 0.20, "equities were {:?}", equities);
+}
+
+#[test]
+fn multiway_split_pots() {
+    //three ace kings mostly split the pot three ways
+    let mut calculator = EquityCalculator::new();
+    for range in ["AsKd", "AcKh", "AhKc"].iter() {
+        calculator.add_range(&SimpleRange::new_from_string(range).unwrap());
+    }
+
+    let mut rng = XorShiftRng::from_seed([13, 14, 15, 16]);
+    let result = calculator.monte_carlo(5_000, &mut rng).unwrap();
+    check_result(&result);
+    for player in result.players.iter() {
+        assert!(player.tie > 0.8, "tie was {}", player.tie);
+        assert!((player.equity - 1.0 / 3.0).abs() < 0.05, "equity was {}", player.equity);
+    }
+}
+
+fn heads_up(one: &str, two: &str) -> EquityCalculator {
+    let mut calculator = EquityCalculator::new();
+    calculator.add_range(&SimpleRange::new_from_string(one).unwrap());
+    calculator.add_range(&SimpleRange::new_from_string(two).unwrap());
+    calculator
+}
+
+#[test]
+fn equity_on_the_river_and_turn() {
+    let mut calculator = heads_up("AhAs", "KdKc");
+    let mut board = vec![Card::new(Value::King, Suit::Hearts), Card::new(Value::Seven, Suit::Clubs),
+        Card::new(Value::Two, Suit::Diamonds), Card::new(Value::Nine, Suit::Spades)];
+
+    //only the two remaining aces of the 44 rivers save the aces
+    calculator.set_board(&board).unwrap();
+    let result = calculator.exact().unwrap();
+    check_result(&result);
+    assert_eq!(result.showdowns, 44);
+    assert_eq!(close(result.players[0].equity, 2.0 / 44.0), true);
+
+    board.push(Card::new(Value::Three, Suit::Hearts));
+    calculator.set_board(&board).unwrap();
+    let result = calculator.exact().unwrap();
+    assert_eq!(result.showdowns, 1);
+    assert_eq!(result.players[1].win, 1.0);
+    assert_eq!(calculator.get_board(), &board[..]);
+}
+
+#[test]
+fn board_and_dead_cards_remove_combos() {
+    let mut calculator = heads_up("KK", "AA");
+    calculator.set_board(&[Card::new(Value::King, Suit::Hearts), Card::new(Value::Seven, Suit::Clubs),
+        Card::new(Value::Two, Suit::Diamonds)]).unwrap();
+
+    //3 combos of kings and 6 of aces, with 45 cards left for the turn and river
+    let result = calculator.exact().unwrap();
+    check_result(&result);
+    assert_eq!(result.showdowns, 3 * 6 * 990);
+    assert!(result.players[0].equity > 0.9, "equity of the set was {}", result.players[0].equity);
+
+    //sampling comes close to the exact result
+    let mut rng = XorShiftRng::from_seed([17, 18, 19, 20]);
+    let sampled = calculator.calculate(1_000, &mut rng).unwrap();
+    assert_eq!(sampled.showdowns, 1_000);
+    let (exact, sampled) = (result.players[0].equity, sampled.players[0]);
+    assert!((sampled.equity - exact).abs() < 4.0 * sampled.std_error, "equity was {}, not {}", sampled.equity, exact);
+
+    //only AhAc is left, out of 43 cards, and the exact result is small enough for calculate
+    calculator.set_dead_cards(&[Card::new(Value::Ace, Suit::Spades), Card::new(Value::Ace, Suit::Diamonds)]).unwrap();
+    let result = calculator.exact().unwrap();
+    assert_eq!(result.showdowns, 3 * 903);
+    assert_eq!(calculator.calculate(10_000, &mut rng).unwrap(), result);
+}
+
+#[test]
+fn results_do_not_depend_on_threads() {
+    let mut calculator = heads_up("QQ+,AKs", "TT-88,AQs");
+    calculator.set_board(&[Card::new(Value::Queen, Suit::Hearts), Card::new(Value::Eight, Suit::Clubs),
+        Card::new(Value::Two, Suit::Diamonds), Card::new(Value::Ten, Suit::Spades)]).unwrap();
+
+    let mut results = Vec::new();
+    for &threads in [1, 3, 8].iter() {
+        calculator.set_threads(threads);
+        let mut rng = XorShiftRng::from_seed([21, 22, 23, 24]);
+        results.push((calculator.exact().unwrap(), calculator.monte_carlo(5_500, &mut rng).unwrap(),
+            calculator.monte_carlo_until(0.02, 100_000, &mut rng).unwrap()));
+    }
+    assert_eq!(results[0], results[1]);
+    assert_eq!(results[0], results[2]);
+}
+
+#[test]
+fn progress_and_cancellation() {
+    let mut calculator = heads_up("AA", "KK");
+    calculator.set_board(&[Card::new(Value::Queen, Suit::Hearts), Card::new(Value::Eight, Suit::Clubs),
+        Card::new(Value::Two, Suit::Diamonds)]).unwrap();
+
+    let shares = Arc::new(Mutex::new(Vec::new()));
+    let reported = shares.clone();
+    calculator.set_progress(move |share| reported.lock().unwrap().push(share));
+    calculator.exact().unwrap();
+    {
+        let shares = shares.lock().unwrap();
+        assert_eq!(shares.windows(2).all(|pair| pair[0] <= pair[1]), true);
+        assert_eq!(shares.last(), Some(&1.0));
+    }
+
+    //cancelling only stops the calculations which are running
+    let handle = calculator.cancel_handle();
+    handle.cancel();
+    let mut rng = XorShiftRng::from_seed([25, 26, 27, 28]);
+    assert_eq!(calculator.monte_carlo(1_000, &mut rng).is_ok(), true);
+
+    //cancelled from the progress callback, while the calculation runs
+    let cancel = calculator.cancel_handle();
+    calculator.set_progress(move |_| cancel.cancel());
+    assert_eq!(calculator.exact(), Err(EquityError::Cancelled));
+    assert_eq!(calculator.monte_carlo(100_000, &mut rng), Err(EquityError::Cancelled));
+    assert_eq!(calculator.monte_carlo_until(0.001, 100_000, &mut rng), Err(EquityError::Cancelled));
+
+    //the next calculation runs as usual
+    calculator.set_progress(|_| {});
+    assert_eq!(calculator.monte_carlo(1_000, &mut rng).is_ok(), true);
+}
+
+#[test]
+fn equity_errors() {
+    let range = SimpleRange::new_from_string("AhAs").unwrap();
+
+    let mut calculator = EquityCalculator::new();
+    calculator.add_range(&range);
+    assert_eq!(calculator.exact(), Err(EquityError::PlayerCount { count: 1 }));
+
+    calculator.add_range(&SimpleRange::new_from_string("AhKh").unwrap());
+    assert_eq!(calculator.exact(), Err(EquityError::NoShowdowns));
+
+    //there are not enough aces for a third player
+    let mut calculator = EquityCalculator::new();
+    for _ in 0..3 {
+        calculator.add_range(&SimpleRange::new_from_string("AA").unwrap());
+    }
+    assert_eq!(calculator.exact(), Err(EquityError::NoShowdowns));
+
+    let mut calculator = EquityCalculator::new();
+    for _ in 0..11 {
+        calculator.add_range(&SimpleRange::new_from_string("xx").unwrap());
+    }
+    assert_eq!(calculator.exact(), Err(EquityError::PlayerCount { count: 11 }));
+
+    let mut calculator = heads_up("AhAs", "KdKc");
+    let ace = Card::new(Value::Ace, Suit::Hearts);
+    let seven = Card::new(Value::Seven, Suit::Clubs);
+    assert_eq!(calculator.set_board(&[ace, seven]), Err(EquityError::BoardSize { count: 2 }));
+    assert_eq!(calculator.set_board(&[ace, seven, seven]), Err(EquityError::DuplicateCard { card: seven }));
+    calculator.set_dead_cards(&[seven]).unwrap();
+    assert_eq!(calculator.set_board(&[seven, ace, Card::new(Value::Two, Suit::Clubs)]), Err(EquityError::DuplicateCard { card: seven }));
+
+    //the board takes the only hand of the first player
+    calculator.set_board(&[ace, Card::new(Value::Two, Suit::Clubs), Card::new(Value::Three, Suit::Clubs)]).unwrap();
+    assert_eq!(calculator.exact(), Err(EquityError::NoShowdowns));
+}
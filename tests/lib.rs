@@ -1,8 +1,14 @@
+#![allow(clippy::bool_assert_comparison)]
+
+extern crate rand;
+
 extern crate cards;
 extern crate pokerhandrange;
 
+use rand::{SeedableRng, StdRng, XorShiftRng};
 use cards::card::{Card, Value, Suit};
-use pokerhandrange::{Range, SimpleRange};
+use cards::deck::Deck;
+use pokerhandrange::{Range, SimpleRange, WeightedRange, RangeParseError, DrawError, HandRanking, ComboCounts, Sampling, RangeMatrix, RangeChart};
 
 //TODO: there could be way more tests here. And unit tests in the modules.
 
@@ -155,3 +161,802 @@ fn construct_range_two_cards_s() {
     assert_eq!(range_p.contains((&cjh, &cts)), true);
 }
 
+
+#[test]
+fn parse_errors() {
+    let error = SimpleRange::new_from_string("AA,K1").err().unwrap();
+    assert_eq!(error, RangeParseError::UnknownRank { token: "1".to_string(), offset: 4 });
+
+    let error = SimpleRange::new_from_string("AA,JJs").err().unwrap();
+    assert_eq!(error, RangeParseError::InvalidPairModifier { token: "JJs".to_string(), offset: 3 });
+
+    let error = SimpleRange::new_from_string("AKs,KQx").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "x".to_string(), offset: 6 });
+
+    let error = SimpleRange::new_from_string("JJ-KQ").err().unwrap();
+    assert_eq!(error, RangeParseError::BadDashRange { token: "JJ-KQ".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("AA,,KK").err().unwrap();
+    assert_eq!(error, RangeParseError::EmptyComponent { token: "".to_string(), offset: 3 });
+    assert_eq!(error.offset(), 3);
+
+    let error = SimpleRange::new_from_string("").err().unwrap();
+    assert_eq!(error, RangeParseError::EmptyInput { token: "".to_string(), offset: 0 });
+
+    //offsets are in bytes, so they can be used to slice the input
+    let error = SimpleRange::new_from_string("AA,♠K").err().unwrap();
+    assert_eq!(error, RangeParseError::UnknownRank { token: "♠".to_string(), offset: 3 });
+    let error = SimpleRange::new_from_string("♠♠,1Ks").err().unwrap();
+    assert_eq!(error, RangeParseError::UnknownRank { token: "♠".to_string(), offset: 0 });
+    let error = SimpleRange::new_from_string("AA,1Ks").err().unwrap();
+    assert_eq!(error, RangeParseError::UnknownRank { token: "1".to_string(), offset: 3 });
+
+    let error = SimpleRange::new_from_string("AKQJT9").err().unwrap();
+    assert_eq!(error.token(), "AKQJT9");
+}
+
+#[test]
+fn construct_range_tolerant_syntax() {
+    let cah = Card::new(Value::Ace, Suit::Hearts);
+    let cas = Card::new(Value::Ace, Suit::Spades);
+    let ckh = Card::new(Value::King, Suit::Hearts);
+    let cks = Card::new(Value::King, Suit::Spades);
+    let cqh = Card::new(Value::Queen, Suit::Hearts);
+    let cjs = Card::new(Value::Jack, Suit::Spades);
+    let cjh = Card::new(Value::Jack, Suit::Hearts);
+
+    let range = SimpleRange::new_from_string("aa, AKs , kq o+").unwrap();
+    assert_eq!(range.get_component_count(), 3);
+    assert_eq!(range.contains((&cah, &cas)), true);
+    assert_eq!(range.contains((&cah, &ckh)), true);
+    assert_eq!(range.contains((&cks, &cqh)), true);
+    assert_eq!(range.contains((&cks, &cjh)), false);
+    assert_eq!(range.contains((&ckh, &cqh)), false);
+
+    let range = SimpleRange::new_from_string("QQ; jj  AK\tT9s").unwrap();
+    assert_eq!(range.get_component_count(), 5);
+    assert_eq!(range.contains((&cjs, &cjh)), true);
+
+    let range = SimpleRange::new_from_string(" jj - qq ").unwrap();
+    assert_eq!(range.get_component_count(), 2);
+
+    let error = SimpleRange::new_from_string("AA, ;KK").err().unwrap();
+    assert_eq!(error, RangeParseError::EmptyComponent { token: "".to_string(), offset: 3 });
+
+    let error = SimpleRange::new_from_string("AA kq x").err().unwrap();
+    assert_eq!(error, RangeParseError::UnrecognizedComponent { token: "x".to_string(), offset: 6 });
+
+    let error = SimpleRange::new_from_string("AA kq ox").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "x".to_string(), offset: 7 });
+
+    let error = SimpleRange::new_from_string(" \t ").err().unwrap();
+    assert_eq!(error, RangeParseError::EmptyInput { token: "".to_string(), offset: 0 });
+}
+
+#[test]
+fn construct_range_dash_spans() {
+    let cah = Card::new(Value::Ace, Suit::Hearts);
+    let cas = Card::new(Value::Ace, Suit::Spades);
+    let c2h = Card::new(Value::Two, Suit::Hearts);
+    let c5h = Card::new(Value::Five, Suit::Hearts);
+    let c6h = Card::new(Value::Six, Suit::Hearts);
+    let c5s = Card::new(Value::Five, Suit::Spades);
+
+    let range = SimpleRange::new_from_string("A2s-A5s").unwrap();
+    assert_eq!(range.get_component_count(), 4);
+    assert_eq!(range.contains((&cah, &c2h)), true);
+    assert_eq!(range.contains((&cah, &c5h)), true);
+    assert_eq!(range.contains((&cah, &c6h)), false);
+    assert_eq!(range.contains((&cas, &c5h)), false);
+
+    let range = SimpleRange::new_from_string("KQo-KTo").unwrap();
+    assert_eq!(range.get_component_count(), 3);
+
+    let range = SimpleRange::new_from_string("A5-A2").unwrap();
+    assert_eq!(range.get_component_count(), 8);
+    assert_eq!(range.contains((&cas, &c5h)), true);
+
+    let range = SimpleRange::new_from_string("54s-98s").unwrap();
+    assert_eq!(range.get_component_count(), 5);
+    assert_eq!(range.contains((&c6h, &c5h)), true);
+    assert_eq!(range.contains((&c6h, &c5s)), false);
+
+    let range = SimpleRange::new_from_string("T8o - 64o").unwrap();
+    assert_eq!(range.get_component_count(), 5);
+
+    let error = SimpleRange::new_from_string("A2s-K5s").err().unwrap();
+    assert_eq!(error, RangeParseError::BadDashRange { token: "A2s-K5s".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("A2s-A5o").err().unwrap();
+    assert_eq!(error, RangeParseError::BadDashRange { token: "A2s-A5o".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("A2s-A5+").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "+".to_string(), offset: 6 });
+
+    let error = SimpleRange::new_from_string("22s-55s").err().unwrap();
+    assert_eq!(error, RangeParseError::InvalidPairModifier { token: "22s-55s".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("JJ-AK").err().unwrap();
+    assert_eq!(error, RangeParseError::BadDashRange { token: "JJ-AK".to_string(), offset: 0 });
+}
+
+#[test]
+fn construct_range_wildcards() {
+    let cah = Card::new(Value::Ace, Suit::Hearts);
+    let cas = Card::new(Value::Ace, Suit::Spades);
+    let ckh = Card::new(Value::King, Suit::Hearts);
+    let c2h = Card::new(Value::Two, Suit::Hearts);
+    let c2s = Card::new(Value::Two, Suit::Spades);
+
+    let range = SimpleRange::new_from_string("Axs").unwrap();
+    assert_eq!(range.get_component_count(), 12);
+    assert_eq!(range.contains((&cah, &c2h)), true);
+    assert_eq!(range.contains((&cah, &ckh)), true);
+    assert_eq!(range.contains((&cah, &c2s)), false);
+    assert_eq!(range.contains((&cah, &cas)), false);
+
+    let range = SimpleRange::new_from_string("Kxo").unwrap();
+    assert_eq!(range.get_component_count(), 11);
+    assert_eq!(range.contains((&ckh, &c2s)), true);
+    assert_eq!(range.contains((&ckh, &c2h)), false);
+    assert_eq!(range.contains((&ckh, &cas)), false);
+
+    let range = SimpleRange::new_from_string("Q*").unwrap();
+    assert_eq!(range.get_component_count(), 20);
+
+    let range = SimpleRange::new_from_string("xx").unwrap();
+    assert_eq!(range.get_component_count(), 169);
+    assert_eq!(range.contains((&cah, &cas)), true);
+    assert_eq!(range.contains((&c2s, &ckh)), true);
+
+    let range = SimpleRange::new_from_string("xXs").unwrap();
+    assert_eq!(range.get_component_count(), 78);
+    assert_eq!(range.contains((&cah, &cas)), false);
+
+    let error = SimpleRange::new_from_string("Ax+").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "+".to_string(), offset: 2 });
+
+    let error = SimpleRange::new_from_string("2x").err().unwrap();
+    assert_eq!(error, RangeParseError::UnknownRank { token: "x".to_string(), offset: 1 });
+}
+
+#[test]
+fn construct_range_combos() {
+    let cah = Card::new(Value::Ace, Suit::Hearts);
+    let cas = Card::new(Value::Ace, Suit::Spades);
+    let cad = Card::new(Value::Ace, Suit::Diamonds);
+    let ckh = Card::new(Value::King, Suit::Hearts);
+    let cks = Card::new(Value::King, Suit::Spades);
+    let ckd = Card::new(Value::King, Suit::Diamonds);
+
+    let range = SimpleRange::new_from_string("AhKh,AsAd").unwrap();
+    assert_eq!(range.get_component_count(), 2);
+    assert_eq!(range.contains((&cah, &ckh)), true);
+    assert_eq!(range.contains((&ckh, &cah)), true);
+    assert_eq!(range.contains((&cas, &cks)), false);
+    assert_eq!(range.contains((&cad, &cas)), true);
+    assert_eq!(range.contains((&cah, &cas)), false);
+
+    for _ in 0..20 {
+        let drawn = range.draw();
+        assert_eq!(range.contains((&drawn.0, &drawn.1)), true);
+    }
+
+    let range = SimpleRange::new_from_string("A♠K♦").unwrap();
+    assert_eq!(range.contains((&cas, &ckd)), true);
+    assert_eq!(range.contains((&cas, &cks)), false);
+
+    let range = SimpleRange::new_from_string("AxKx").unwrap();
+    assert_eq!(range.contains((&cas, &cks)), true);
+    assert_eq!(range.contains((&cah, &ckh)), true);
+    assert_eq!(range.contains((&cas, &ckd)), false);
+
+    //combos already covered by a component do not count twice
+    let range = SimpleRange::new_from_string("AKs,AhKh,7c7d").unwrap();
+    assert_eq!(range.get_component_count(), 2);
+
+    let error = SimpleRange::new_from_string("AhAh").err().unwrap();
+    assert_eq!(error, RangeParseError::DuplicateCard { token: "AhAh".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("AhKq").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "q".to_string(), offset: 3 });
+}
+
+#[test]
+fn construct_range_suit_patterns() {
+    let cah = Card::new(Value::Ace, Suit::Hearts);
+    let cas = Card::new(Value::Ace, Suit::Spades);
+    let ckh = Card::new(Value::King, Suit::Hearts);
+    let cks = Card::new(Value::King, Suit::Spades);
+    let ckd = Card::new(Value::King, Suit::Diamonds);
+    let ckc = Card::new(Value::King, Suit::Clubs);
+    let c2s = Card::new(Value::Two, Suit::Spades);
+    let c8h = Card::new(Value::Eight, Suit::Hearts);
+    let c8c = Card::new(Value::Eight, Suit::Clubs);
+    let c8d = Card::new(Value::Eight, Suit::Diamonds);
+
+    //any ace of spades with another spade
+    let range = SimpleRange::new_from_string("AsXs").unwrap();
+    assert_eq!(range.get_component_count(), 12);
+    assert_eq!(range.contains((&cas, &cks)), true);
+    assert_eq!(range.contains((&c2s, &cas)), true);
+    assert_eq!(range.contains((&cah, &ckh)), false);
+    assert_eq!(range.contains((&cas, &ckh)), false);
+
+    //KK with a heart
+    let range = SimpleRange::new_from_string("KhKx").unwrap();
+    assert_eq!(range.get_component_count(), 3);
+    assert_eq!(range.contains((&cks, &ckh)), true);
+    assert_eq!(range.contains((&cks, &ckd)), false);
+
+    //88 without clubs
+    let range = SimpleRange::new_from_string("8[shd]8[shd]").unwrap();
+    assert_eq!(range.get_component_count(), 3);
+    assert_eq!(range.contains((&c8h, &c8d)), true);
+    assert_eq!(range.contains((&c8h, &c8c)), false);
+
+    //different variables are different suits
+    let range = SimpleRange::new_from_string("AxKy").unwrap();
+    assert_eq!(range.get_component_count(), 1);
+    assert_eq!(range.contains((&cah, &ckc)), true);
+    assert_eq!(range.contains((&cah, &ckh)), false);
+
+    //'*' as a suit is any suit, upper case 'X' only stands for a rank
+    let range = SimpleRange::new_from_string("A*K*").unwrap();
+    assert_eq!(range.combo_counts().total(), 16.0);
+    assert_eq!(range.contains((&cah, &ckh)), true);
+    assert_eq!(range.contains((&cah, &ckc)), true);
+
+    let error = SimpleRange::new_from_string("AXKX").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "X".to_string(), offset: 1 });
+
+    let error = SimpleRange::new_from_string("AXKY").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "X".to_string(), offset: 1 });
+
+    let range = SimpleRange::new_from_string("AhKw").unwrap();
+    assert_eq!(range.get_component_count(), 4);
+    for _ in 0..20 {
+        let drawn = range.draw();
+        assert_eq!(range.contains((&drawn.0, &drawn.1)), true);
+    }
+
+    //complete sets of combos become the component they add up to
+    let range = SimpleRange::new_from_string("AhKh,AsKs,AdKd,AcKc").unwrap();
+    assert_eq!(range.get_component_count(), 1);
+
+    let error = SimpleRange::new_from_string("AxAx").err().unwrap();
+    assert_eq!(error, RangeParseError::DuplicateCard { token: "AxAx".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("8[sq]8s").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "q".to_string(), offset: 3 });
+
+    let error = SimpleRange::new_from_string("8[sh").err().unwrap();
+    assert_eq!(error, RangeParseError::UnrecognizedComponent { token: "8[sh".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("AhKhs").err().unwrap();
+    assert_eq!(error, RangeParseError::BadModifier { token: "s".to_string(), offset: 4 });
+}
+
+#[test]
+fn construct_range_exclusions() {
+    let c5h = Card::new(Value::Five, Suit::Hearts);
+    let c5s = Card::new(Value::Five, Suit::Spades);
+    let c6h = Card::new(Value::Six, Suit::Hearts);
+    let c6s = Card::new(Value::Six, Suit::Spades);
+    let cah = Card::new(Value::Ace, Suit::Hearts);
+    let cas = Card::new(Value::Ace, Suit::Spades);
+    let cad = Card::new(Value::Ace, Suit::Diamonds);
+
+    let range = SimpleRange::new_from_string("22+,!55").unwrap();
+    assert_eq!(range.get_component_count(), 12);
+    assert_eq!(range.contains((&c5h, &c5s)), false);
+    assert_eq!(range.contains((&c6h, &c6s)), true);
+
+    let range = SimpleRange::new_from_string("A2s+ ~A5s").unwrap();
+    assert_eq!(range.get_component_count(), 11);
+    assert_eq!(range.contains((&cah, &c5h)), false);
+    assert_eq!(range.contains((&cah, &c6h)), true);
+
+    let range = SimpleRange::new_from_string("22-77,-55-66").unwrap();
+    assert_eq!(range.get_component_count(), 4);
+
+    //a dash glued to a rank after whitespace negates, a dash with whitespace after it spans
+    let range = SimpleRange::new_from_string("A2s+ -A5s").unwrap();
+    assert_eq!(range, SimpleRange::new_from_string("A2s+ ~A5s").unwrap());
+    let error = SimpleRange::new_from_string("AA -KK").err().unwrap();
+    assert_eq!(error, RangeParseError::ExclusionNotInRange { token: "-KK".to_string(), offset: 3 });
+    let range = SimpleRange::new_from_string("QQ+ -KK").unwrap();
+    assert_eq!(range.get_canonical_text(), "AA,QQ");
+    assert_eq!(SimpleRange::new_from_string("AA - KK").unwrap().get_canonical_text(), "KK+");
+    assert_eq!(SimpleRange::new_from_string("AA- KK").unwrap().get_canonical_text(), "KK+");
+
+    //excluding a single combo splits up its component
+    let range = SimpleRange::new_from_string("AA,!AsAh").unwrap();
+    assert_eq!(range.get_component_count(), 5);
+    assert_eq!(range.contains((&cah, &cas)), false);
+    assert_eq!(range.contains((&cad, &cas)), true);
+    for _ in 0..20 {
+        let drawn = range.draw();
+        assert_eq!(range.contains((&drawn.0, &drawn.1)), true);
+    }
+
+    //evaluated from left to right
+    let range = SimpleRange::new_from_string("22+,!55,55").unwrap();
+    assert_eq!(range.contains((&c5h, &c5s)), true);
+
+    let error = SimpleRange::new_from_string("!55,22+").err().unwrap();
+    assert_eq!(error, RangeParseError::ExclusionNotInRange { token: "!55".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("AKs,!AK").err().unwrap();
+    assert_eq!(error, RangeParseError::ExclusionNotInRange { token: "!AK".to_string(), offset: 4 });
+
+    let error = SimpleRange::new_from_string("AA,!AA").err().unwrap();
+    assert_eq!(error, RangeParseError::EmptyRange { token: "AA,!AA".to_string(), offset: 0 });
+}
+
+#[test]
+fn construct_weighted_range() {
+    let cah = Card::new(Value::Ace, Suit::Hearts);
+    let cas = Card::new(Value::Ace, Suit::Spades);
+    let ckh = Card::new(Value::King, Suit::Hearts);
+    let cks = Card::new(Value::King, Suit::Spades);
+    let cqh = Card::new(Value::Queen, Suit::Hearts);
+    let cqs = Card::new(Value::Queen, Suit::Spades);
+    let c5h = Card::new(Value::Five, Suit::Hearts);
+    let c2h = Card::new(Value::Two, Suit::Hearts);
+
+    let range = WeightedRange::new_from_string("AA,KK:0.5,AKs:75%").unwrap();
+    assert_eq!(range.get_component_count(), 3);
+    assert_eq!(range.weight_of((&cah, &cas)), 1.0);
+    assert_eq!(range.weight_of((&ckh, &cks)), 0.5);
+    assert_eq!(range.weight_of((&cah, &ckh)), 0.75);
+    assert_eq!(range.weight_of((&cah, &cks)), 0.0);
+    assert_eq!(range.contains((&ckh, &cks)), true);
+    assert_eq!(range.contains((&cqh, &cqs)), false);
+
+    let range = WeightedRange::new_from_string("[50]A5s-A2s[/50], QQ").unwrap();
+    assert_eq!(range.weight_of((&cah, &c5h)), 0.5);
+    assert_eq!(range.weight_of((&cah, &c2h)), 0.5);
+    assert_eq!(range.weight_of((&cqh, &cqs)), 1.0);
+
+    let range = WeightedRange::new_from_string("[25] KK, QQ [/25] AA").unwrap();
+    assert_eq!(range.weight_of((&ckh, &cks)), 0.25);
+    assert_eq!(range.weight_of((&cqh, &cqs)), 0.25);
+    assert_eq!(range.weight_of((&cah, &cas)), 1.0);
+
+    //the component further right decides, down to single combos
+    let range = WeightedRange::new_from_string("AA:0.5,AsAh").unwrap();
+    assert_eq!(range.get_component_count(), 6);
+    assert_eq!(range.weight_of((&cah, &cas)), 1.0);
+    assert_eq!(range.weight_of((&cah, &Card::new(Value::Ace, Suit::Clubs))), 0.5);
+
+    //overlapping suit patterns split up into single combos, which keep their own weights
+    let range = WeightedRange::new_from_string("xx:0.5,xsxh:0.7,xdxc:0.3").unwrap();
+    assert_eq!(range.weight_of((&cas, &c5h)), 0.7);
+    assert_eq!(range.weight_of((&Card::new(Value::Ace, Suit::Diamonds), &Card::new(Value::Two, Suit::Clubs))), 0.3);
+    assert_eq!(range.weight_of((&cah, &c5h)), 0.5);
+    assert_eq!(range.clone().combo_counts(), range.combo_counts());
+
+    //zero weights and exclusions drop hands
+    let range = WeightedRange::new_from_string("AA,KK:0,QQ:0.3,!AA").unwrap();
+    assert_eq!(range.get_component_count(), 1);
+    for _ in 0..20 {
+        let drawn = range.draw();
+        assert_eq!(range.weight_of((&drawn.0, &drawn.1)), 0.3);
+    }
+
+    let error = WeightedRange::new_from_string("AA,KK:1.5").err().unwrap();
+    assert_eq!(error, RangeParseError::BadWeight { token: ":1.5".to_string(), offset: 5 });
+
+    let error = WeightedRange::new_from_string("KK:x").err().unwrap();
+    assert_eq!(error, RangeParseError::BadWeight { token: ":x".to_string(), offset: 2 });
+
+    let error = WeightedRange::new_from_string("[50]KK,QQ").err().unwrap();
+    assert_eq!(error, RangeParseError::BadWeight { token: "[50]".to_string(), offset: 0 });
+
+    let error = WeightedRange::new_from_string("[50]KK[/40]").err().unwrap();
+    assert_eq!(error, RangeParseError::BadWeight { token: "[/40]".to_string(), offset: 6 });
+
+    let error = WeightedRange::new_from_string("AA:0").err().unwrap();
+    assert_eq!(error, RangeParseError::EmptyRange { token: "AA:0".to_string(), offset: 0 });
+
+    let error = SimpleRange::new_from_string("AA,KK:0.5").err().unwrap();
+    assert_eq!(error, RangeParseError::UnexpectedWeight { token: "KK:0.5".to_string(), offset: 3 });
+}
+
+#[test]
+fn draw_weighted_range_proportionally() {
+    //AA has 6 combos with weight 1, AKs 4 combos with weight 0.5, so 3 in 4 draws are AA
+    let range = WeightedRange::new_from_string("AA,AKs:0.5").unwrap();
+    let draws = 4000;
+    let mut pairs = 0;
+    for _ in 0..draws {
+        let drawn = range.draw();
+        if drawn.0.value == drawn.1.value {
+            pairs += 1;
+        }
+    }
+
+    let share = pairs as f64 / draws as f64;
+    assert!(share > 0.7 && share < 0.8, "share of pairs was {}", share);
+}
+
+#[test]
+fn canonical_range_text() {
+    let canonical = |text: &str| SimpleRange::new_from_string(text).unwrap().to_string();
+
+    assert_eq!(canonical("AKs,AQs,AJs"), "AJs+");
+    assert_eq!(canonical("AJs+"), "AJs+");
+    assert_eq!(canonical("QQ,KK,AA,22-44"), "QQ+,44-22");
+    assert_eq!(canonical("99"), "99");
+    assert_eq!(canonical("A2s-A5s,ATs+,KQo"), "ATs+,A5s-A2s,KQo");
+    assert_eq!(canonical("AT+"), "AT+");
+    assert_eq!(canonical("A2s+,AKo"), "A2s+,AKo");
+    assert_eq!(canonical("AA,!AsAh"), "AsAd,AhAd,AsAc,AhAc,AdAc");
+    assert_eq!(canonical("KQs,AhKh"), "KQs,AhKh");
+    assert_eq!(canonical("xx"), "22+,A2+,K2+,Q2+,J2+,T2+,92+,82+,72+,62+,52+,42+,32");
+
+    let range : SimpleRange = "AKs,AQs,AJs".parse().unwrap();
+    let other : SimpleRange = "AJs+".parse().unwrap();
+    assert_eq!(range, other);
+    assert!(range != SimpleRange::new_from_string("AJs").unwrap());
+    assert_eq!(range.get_range_text(), "AKs,AQs,AJs");
+    assert_eq!(range.get_canonical_text(), "AJs+");
+}
+
+#[test]
+fn contains_every_combo_exactly_once() {
+    let deck = Deck::new_unshuffled();
+    let cards : Vec<Card> = deck_cards(deck);
+
+    let everything = SimpleRange::new_from_string("xx").unwrap();
+    let suited_aces = SimpleRange::new_from_string("AKs,AsXs").unwrap();
+
+    let mut suited_count = 0;
+    for (i, a) in cards.iter().enumerate() {
+        for b in cards[i + 1..].iter() {
+            assert_eq!(everything.contains((a, b)), true);
+            assert_eq!(suited_aces.contains((a, b)), suited_aces.contains((b, a)));
+            if suited_aces.contains((a, b)) {
+                suited_count += 1;
+                assert_eq!(a.suit, b.suit);
+            }
+        }
+    }
+    assert_eq!(suited_count, 15);
+
+    for _ in 0..100 {
+        let drawn = suited_aces.draw();
+        assert!(drawn.0 != drawn.1);
+        assert_eq!(suited_aces.contains((&drawn.0, &drawn.1)), true);
+    }
+}
+
+fn deck_cards(mut deck: Deck) -> Vec<Card> {
+    let mut cards = Vec::new();
+    while let Ok(card) = deck.draw() {
+        cards.push(card);
+    }
+    cards
+}
+
+#[test]
+fn range_set_algebra() {
+    let range = |text: &str| SimpleRange::new_from_string(text).unwrap();
+
+    let opening = range("22+,A2s+,KTs+,ATo+");
+    let three_bet = range("TT+,AQs+,AKo");
+
+    let calling = &opening - &three_bet;
+    assert_eq!(calling, range("99-22,AJs-A2s,AQo-ATo,KTs+"));
+    assert_eq!(calling.get_range_text(), "99-22,AJs-A2s,AQo-ATo,KTs+");
+
+    let suited = range("xxs");
+    assert_eq!((&calling & &suited).to_string(), "AJs-A2s,KTs+");
+    assert_eq!((&three_bet | &range("99")).to_string(), "99+,AK,AQs");
+    assert_eq!(opening.union(&three_bet), opening);
+    assert_eq!(three_bet.intersection(&opening), three_bet);
+
+    //down to single combos
+    let aces = range("AA") - range("AsAh");
+    assert_eq!(aces, range("AA,!AsAh"));
+
+    let everything = range("xx");
+    assert_eq!(!&everything - range("AA"), !range("xx"));
+    assert_eq!((!range("xx")).is_empty(), true);
+
+    //empty ranges are written as "none", which parses back to them
+    let empty = &aces - &aces;
+    assert_eq!(empty.to_string(), "none");
+    assert_eq!(empty.to_string().parse::<SimpleRange>().unwrap(), empty);
+    assert_eq!(range(" None ").is_empty(), true);
+    assert_eq!(range("none").draw_excluding(&[]), Err(DrawError::NoCombosLeft));
+    assert_eq!(!!range("AKs,7c7d"), range("AKs,7c7d"));
+    assert_eq!(range("AKs").complement() | range("AKs"), everything);
+    assert_eq!((!range("AKs")).to_string().contains("AKs"), false);
+}
+
+#[test]
+fn top_percent_ranges() {
+    let range = |text: &str| SimpleRange::new_from_string(text).unwrap();
+    let pokerstove = HandRanking::pokerstove();
+
+    assert_eq!(SimpleRange::top_percent(0.0, &pokerstove).is_empty(), true);
+    assert_eq!(SimpleRange::top_percent(0.0, &pokerstove), range("none"));
+    assert_eq!(SimpleRange::top_percent(0.4, &pokerstove), range("AA"));
+    assert_eq!(SimpleRange::top_percent(1.0, &pokerstove), range("QQ+"));
+    assert_eq!(SimpleRange::top_percent(100.0, &pokerstove), range("xx"));
+    assert_eq!(SimpleRange::top_percent(250.0, &pokerstove), range("xx"));
+    assert_eq!(SimpleRange::top_percent(f64::NAN, &pokerstove).is_empty(), true);
+
+    //the range language uses the PokerStove ranking
+    assert_eq!(range("3%"), range("99+,AKs"));
+    assert_eq!(range("3%").get_range_text(), "3%");
+    assert_eq!(range("3%,!AKs,22"), range("99+,22"));
+    assert_eq!(range("10%"), range("77+,A9s+,KTs+,QTs+,AJo+,KQo"));
+    assert_eq!(range("100%"), range("xx"));
+
+    assert_eq!(HandRanking::sklansky_chubukov().get_hand_count(), 169);
+    assert_eq!(HandRanking::equity_vs_random().get_hand_count(), 169);
+    assert_eq!(SimpleRange::top_percent(1.0, &HandRanking::sklansky_chubukov()), range("KK+,AKs"));
+    assert_eq!(SimpleRange::top_percent(3.0, &HandRanking::equity_vs_random()), range("88+"));
+
+    let custom : HandRanking = "AA, AKs, KK".parse().unwrap();
+    assert_eq!(custom.get_hand_count(), 3);
+    assert_eq!(SimpleRange::top_percent(0.5, &custom), range("AA,AKs"));
+    assert_eq!(SimpleRange::top_percent(50.0, &custom), range("KK+,AKs"));
+
+    let err = |text: &str| HandRanking::new_from_string(text).unwrap_err();
+    assert_eq!(err("AA,KK,AA"), RangeParseError::DuplicateHand { token: String::from("AA"), offset: 6 });
+    assert_eq!(err("AA,KK+"), RangeParseError::UnrecognizedComponent { token: String::from("KK+"), offset: 3 });
+    assert_eq!(err("AA,AhAd"), RangeParseError::UnrecognizedComponent { token: String::from("AhAd"), offset: 3 });
+    assert_eq!(SimpleRange::new_from_string("AA,150%").unwrap_err(),
+        RangeParseError::UnrecognizedComponent { token: String::from("150%"), offset: 3 });
+    assert_eq!(SimpleRange::new_from_string("1.2.3%").unwrap_err(),
+        RangeParseError::UnrecognizedComponent { token: String::from("1.2.3%"), offset: 0 });
+}
+
+#[test]
+fn count_combos() {
+    let range = SimpleRange::new_from_string("AA,AKs,AKo,7c7d").unwrap();
+    assert_eq!(range.get_combo_count(), 23);
+    assert_eq!(range.get_component_count(), 4);
+    assert_eq!(range.combo_counts(), ComboCounts { pairs: 7.0, suited: 4.0, offsuit: 12.0 });
+    assert_eq!(range.combo_counts().total(), 23.0);
+    assert_eq!(SimpleRange::new_from_string("xx").unwrap().combo_counts().percentage(), 100.0);
+    assert_eq!((SimpleRange::new_from_string("22").unwrap().combo_counts().percentage() - 6.0 / 13.26).abs() < 1e-9, true);
+
+    //holding the ace of hearts, with the seven of clubs on the board
+    let dead = [Card::new(Value::Ace, Suit::Hearts), Card::new(Value::Seven, Suit::Clubs)];
+    assert_eq!(range.combo_counts_excluding(&dead), ComboCounts { pairs: 3.0, suited: 3.0, offsuit: 9.0 });
+    assert_eq!(range.combo_counts_excluding(&dead[..1]).total(), 16.0);
+
+    let weighted = WeightedRange::new_from_string("AA,KK:0.5,[25]AKs,AQo[/25]").unwrap();
+    assert_eq!(weighted.combo_counts(), ComboCounts { pairs: 9.0, suited: 1.0, offsuit: 3.0 });
+    assert_eq!(weighted.combo_counts_excluding(&dead[..1]).total(), 3.0 + 3.0 + 0.75 + 2.25);
+}
+
+#[test]
+fn draw_excluding_dead_cards() {
+    let ah = Card::new(Value::Ace, Suit::Hearts);
+    let kd = Card::new(Value::King, Suit::Diamonds);
+    let dead = [ah, kd];
+
+    let range = SimpleRange::new_from_string("AA,AKs").unwrap();
+    let combos = range.combos_excluding(&dead);
+    assert_eq!(combos.len(), 3 + 2);
+    assert_eq!(combos.iter().any(|&(a, b)| dead.contains(&a) || dead.contains(&b)), false);
+
+    for _ in 0..100 {
+        let (a, b) = range.draw_excluding(&dead).unwrap();
+        assert_eq!(dead.contains(&a) || dead.contains(&b), false);
+        assert_eq!(range.contains((&a, &b)), true);
+    }
+
+    let weighted = WeightedRange::new_from_string("AA,AKs:0.5").unwrap();
+    assert_eq!(weighted.combos_excluding(&dead).len(), 5);
+    for _ in 0..100 {
+        let (a, b) = weighted.draw_excluding(&dead).unwrap();
+        assert_eq!(dead.contains(&a) || dead.contains(&b), false);
+        assert_eq!(weighted.contains((&a, &b)), true);
+    }
+
+    //no combo left
+    let ace = |suit| Card::new(Value::Ace, suit);
+    let aces = [ace(Suit::Spades), ace(Suit::Hearts), ace(Suit::Diamonds)];
+    assert_eq!(range.draw_excluding(&aces[..1]).is_ok(), true);
+    assert_eq!(SimpleRange::new_from_string("AA").unwrap().draw_excluding(&aces), Err(DrawError::NoCombosLeft));
+    assert_eq!(WeightedRange::new_from_string("AA:0.5").unwrap().draw_excluding(&aces), Err(DrawError::NoCombosLeft));
+    assert_eq!(SimpleRange::new_from_string("AhKd").unwrap().combos_excluding(&dead).is_empty(), true);
+}
+
+#[test]
+fn draw_with_seeded_rng() {
+    let range = SimpleRange::new_from_string("22+,A2s+,KTo+").unwrap();
+    let weighted = WeightedRange::new_from_string("AA,KK:0.5,[25]AKs,AQo[/25]").unwrap();
+    let dead = [Card::new(Value::Ace, Suit::Hearts)];
+
+    let draws = |seed: u32| {
+        let mut rng = XorShiftRng::from_seed([seed, 2, 3, 4]);
+        (0..50).map(|_| (range.draw_with(&mut rng), weighted.draw_with(&mut rng),
+            range.draw_excluding_with(&dead, &mut rng).unwrap(),
+            weighted.draw_excluding_with(&dead, &mut rng).unwrap())).collect::<Vec<_>>()
+    };
+    assert_eq!(draws(1), draws(1));
+    assert_eq!(draws(1) == draws(5), false);
+
+    let seed : &[usize] = &[7, 11];
+    let mut rng_one = StdRng::from_seed(seed);
+    let mut rng_two = StdRng::from_seed(seed);
+    for _ in 0..50 {
+        assert_eq!(range.draw_with(&mut rng_one), range.draw_with(&mut rng_two));
+    }
+}
+
+#[test]
+fn ranges_as_trait_objects() {
+    let ranges : Vec<Box<dyn Range>> = vec![
+        Box::new(SimpleRange::new_from_string("AA,KK").unwrap()),
+        Box::new(WeightedRange::new_from_string("AA,KK:0.5").unwrap()),
+    ];
+    let dead = [Card::new(Value::Ace, Suit::Hearts)];
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+
+    for range in ranges.iter() {
+        let (a, b) = range.draw();
+        assert_eq!(range.contains((&a, &b)), true);
+        let (a, b) = range.draw_with(&mut rng);
+        assert_eq!(range.weight_of((&a, &b)) > 0.0, true);
+        let (a, b) = range.draw_excluding(&dead).unwrap();
+        assert_eq!(dead.contains(&a) || dead.contains(&b), false);
+        assert_eq!(range.draw_with_sampling(&Sampling::UniformByClass, &dead, &mut rng).is_ok(), true);
+        assert_eq!(range.combos_excluding(&dead).len(), 3 + 6);
+        assert_eq!(RangeMatrix::from_range(range.as_ref()).get(0, 0), 1.0);
+    }
+    assert_eq!(ranges[0].combo_counts().pairs, 12.0);
+    assert_eq!(ranges[1].combo_counts().pairs, 9.0);
+}
+
+#[test]
+fn iterate_combos() {
+    let range = SimpleRange::new_from_string("AA,AKs,7c7d").unwrap();
+    let combos : Vec<((Card, Card), f64)> = range.iter_combos().collect();
+    assert_eq!(combos.len(), 11);
+    assert_eq!(combos[0], ((Card::new(Value::Seven, Suit::Diamonds), Card::new(Value::Seven, Suit::Clubs)), 1.0));
+    assert_eq!(combos.iter().all(|&((a, b), weight)| range.contains((&a, &b)) && weight == 1.0 && a.value >= b.value), true);
+
+    let mut unique : Vec<(Card, Card)> = combos.iter().map(|item| item.0).collect();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), 11);
+
+    let dead = [Card::new(Value::Ace, Suit::Spades), Card::new(Value::Seven, Suit::Clubs)];
+    assert_eq!(range.iter_combos_excluding(&dead).count(), 3 + 3);
+    assert_eq!(range.iter_combos_excluding(&dead).any(|((a, b), _)| dead.contains(&a) || dead.contains(&b)), false);
+
+    let weighted = WeightedRange::new_from_string("AA,KK:0.5,AhKh:0").unwrap();
+    assert_eq!(weighted.iter_combos().count(), 12);
+    assert_eq!(weighted.iter_combos().map(|item| item.1).sum::<f64>(), 9.0);
+    assert_eq!(weighted.iter_combos_excluding(&dead).map(|item| item.1).sum::<f64>(), 6.0);
+}
+
+#[test]
+fn draw_with_sampling_strategies() {
+    let range = SimpleRange::new_from_string("AA,AKo").unwrap();
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let draws = 4000;
+
+    let share_of_pairs = |sampling: &Sampling, rng: &mut XorShiftRng| {
+        let pairs = (0..draws)
+            .map(|_| range.draw_with_sampling(sampling, &[], rng).unwrap())
+            .filter(|drawn| drawn.0.value == drawn.1.value)
+            .count();
+        pairs as f64 / draws as f64
+    };
+
+    //6 combos of AA against 12 of AKo
+    let share = share_of_pairs(&Sampling::Combinatorial, &mut rng);
+    assert!(share > 0.28 && share < 0.38, "share of pairs was {}", share);
+    let share = share_of_pairs(&Sampling::UniformByClass, &mut rng);
+    assert!(share > 0.45 && share < 0.55, "share of pairs was {}", share);
+
+    let only_hearts = |hand: (&Card, &Card)| if hand.0.suit == Suit::Hearts || hand.1.suit == Suit::Hearts { 1.0 } else { 0.0 };
+    for _ in 0..100 {
+        let drawn = range.draw_with_sampling(&Sampling::Custom(&only_hearts), &[], &mut rng).unwrap();
+        assert_eq!(drawn.0.suit == Suit::Hearts || drawn.1.suit == Suit::Hearts, true);
+    }
+
+    //weights of the range still count inside of a class
+    let weighted = WeightedRange::new_from_string("AA,AKo,AhKs:0").unwrap();
+    let dead = [Card::new(Value::Ace, Suit::Spades)];
+    for _ in 0..100 {
+        let drawn = weighted.draw_with_sampling(&Sampling::UniformByClass, &dead, &mut rng).unwrap();
+        assert_eq!(weighted.contains((&drawn.0, &drawn.1)) && !dead.contains(&drawn.0) && !dead.contains(&drawn.1), true);
+    }
+
+    let nothing = |_: (&Card, &Card)| 0.0;
+    assert_eq!(range.draw_with_sampling(&Sampling::Custom(&nothing), &[], &mut rng), Err(DrawError::NoCombosLeft));
+}
+
+#[test]
+fn range_matrix() {
+    let range = SimpleRange::new_from_string("QQ+,AKs,AsKh").unwrap();
+    let matrix = RangeMatrix::from(&range);
+
+    assert_eq!(RangeMatrix::cell_name(0, 0), "AA");
+    assert_eq!(RangeMatrix::cell_name(0, 1), "AKs");
+    assert_eq!(RangeMatrix::cell_name(1, 0), "AKo");
+    assert_eq!(RangeMatrix::cell_name(12, 11), "32o");
+    assert_eq!(matrix.get(2, 2), 1.0);
+    assert_eq!(matrix.get(0, 1), 1.0);
+    assert_eq!(matrix.get(1, 0), 1.0 / 12.0);
+    assert_eq!(matrix.get(3, 3), 0.0);
+
+    //partly included cells become complete
+    assert_eq!(matrix.to_simple_range_lossy(), SimpleRange::new_from_string("QQ+,AK").unwrap());
+    let single = RangeMatrix::from(&SimpleRange::new_from_string("AsKh").unwrap());
+    assert_eq!(single.to_simple_range_lossy(), SimpleRange::new_from_string("AKo").unwrap());
+
+    let mut matrix = RangeMatrix::new();
+    assert_eq!(matrix.to_simple_range_lossy().is_empty(), true);
+    assert_eq!(matrix.to_simple_range_lossy().get_range_text(), "none");
+    matrix.set(12, 12, 1.0);
+    matrix.set(0, 12, 2.0);
+    assert_eq!(matrix.get(0, 12), 1.0);
+    matrix.set(1, 12, f64::NAN);
+    assert_eq!(matrix.get(1, 12), 0.0);
+    matrix.set(1, 12, f64::INFINITY);
+    assert_eq!(matrix.get(1, 12), 0.0);
+    assert_eq!(matrix.to_simple_range_lossy(), SimpleRange::new_from_string("22,A2s").unwrap());
+
+    let weighted = WeightedRange::new_from_string("AA,KK:0.5").unwrap();
+    assert_eq!(RangeMatrix::from_range(&weighted).get(1, 1), 0.5);
+
+    let ascii = RangeMatrix::from(&range).render_ascii();
+    let lines : Vec<&str> = ascii.lines().collect();
+    assert_eq!(lines.len(), 13);
+    assert_eq!(lines[0].starts_with("AA # AKs# AQs  AJs "), true);
+    assert_eq!(lines[1].starts_with("AKo+ KK # KQs "), true);
+    assert_eq!(RangeMatrix::from(&range).to_string(), ascii);
+    assert_eq!(RangeMatrix::from(&range).render_colored().contains("AKs ■"), true);
+}
+
+#[test]
+fn export_range_charts() {
+    let matrix = RangeMatrix::from(&SimpleRange::new_from_string("QQ+,AKs,AsKh").unwrap());
+
+    let svg = matrix.to_svg();
+    assert_eq!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""), true);
+    assert_eq!(svg.trim_end().ends_with("</svg>"), true);
+    assert_eq!(svg.matches("<text").count(), 169 + 1);
+    assert_eq!(svg.contains(">AKo</text>"), true);
+    //the AKo cell at (1, 0) is filled for one twelfth of its width
+    assert_eq!(svg.contains("<rect x=\"0.00\" y=\"40\" width=\"3.33\" height=\"40\" fill=\"#4caf50\"/>"), true);
+
+    let html = matrix.to_html();
+    assert_eq!(html.matches("<tr>").count(), 13);
+    assert_eq!(html.matches("<td").count(), 169);
+    assert_eq!(html.contains("title=\"range: 100%\""), true);
+    assert_eq!(html.contains("#4caf50 0.0%, #4caf50 8.3%, #e8e8e8 8.3%"), true);
+
+    //raising half of AA, calling the other half and all of KK
+    let mut raise = RangeMatrix::new();
+    raise.set(0, 0, 0.5);
+    let mut call = RangeMatrix::new();
+    call.set(0, 0, 0.5);
+    call.set(1, 1, 1.0);
+
+    let mut chart = RangeChart::new();
+    chart.add_action("raise <3x>", "red", raise);
+    chart.add_action("call", "blue", call);
+    assert_eq!(chart.get_actions().len(), 2);
+
+    let html = chart.to_html();
+    assert_eq!(html.contains("red 0.0%, red 50.0%, blue 50.0%, blue 100.0%"), true);
+    assert_eq!(html.contains("title=\"raise &lt;3x&gt;: 50%, call: 50%\""), true);
+    assert_eq!(html.contains("<3x>"), false);
+
+    let svg = chart.to_svg();
+    assert_eq!(svg.contains(">raise &lt;3x&gt;</text>"), true);
+    assert_eq!(svg.contains("<rect x=\"20.00\" y=\"0\" width=\"20.00\" height=\"40\" fill=\"blue\"/>"), true);
+}
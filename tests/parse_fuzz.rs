@@ -0,0 +1,103 @@
+extern crate rand;
+
+extern crate cards;
+extern crate pokerhandrange;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+use pokerhandrange::{Range, SimpleRange, WeightedRange, RangeParseError};
+
+const FUZZ_ROUNDS : usize = 20000;
+
+/// Characters which are likely to trigger interesting paths in the parser.
+const ALPHABET : &[char] = &[
+    'A', 'K', 'Q', 'J', 'T', '9', '5', '2', '1', 'X', 'a', 'k',
+    's', 'o', 'u', 'x', 'h', 'c', '+', '-', ',', ';', ' ', '\t', '!', '%', ':',
+    '[', ']', '/', '0', '.',
+    '♠', '♥', 'é', '漢', '🂡', '\u{301}', '\u{0}',
+];
+
+fn random_input<R: Rng>(rng: &mut R) -> String {
+    let length = rng.gen_range(0, 16);
+    let mut input = String::new();
+    for _ in 0..length {
+        if rng.gen_weighted_bool(10) {
+            //any unicode scalar value
+            if let Some(c) = std::char::from_u32(rng.gen_range(0, 0x11_0000)) {
+                input.push(c);
+            }
+        } else {
+            input.push(*rng.choose(ALPHABET).unwrap());
+        }
+    }
+    input
+}
+
+fn check_result<R: Range>(input: &str, result: Result<R, RangeParseError>) {
+    match result {
+        Ok(range) => {
+            let cards = range.draw();
+            assert!(range.contains((&cards.0, &cards.1)), "drawn cards not in '{}'", input);
+        },
+        Err(error) => {
+            let offset = error.offset();
+            assert!(offset <= input.len(), "offset out of bounds for '{}': {}", input, error);
+            assert!(input.is_char_boundary(offset), "offset not on a char boundary for '{}': {}", input, error);
+            assert!(input[offset..].starts_with(error.token()), "token not at offset for '{}': {}", input, error);
+        },
+    }
+}
+
+/// Whatever the input, the parsers return instead of panicking and errors point into the input.
+fn check_input(input: &str) {
+    check_result(input, SimpleRange::new_from_string(input));
+    check_result(input, WeightedRange::new_from_string(input));
+}
+
+#[test]
+fn fuzz_parser_never_panics() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    for _ in 0..FUZZ_ROUNDS {
+        let input = random_input(&mut rng);
+        check_input(&input);
+    }
+}
+
+#[test]
+fn parser_handles_multi_byte_input() {
+    let inputs = ["♠", "♠♠", "A♠", "AK♠", "♠K+", "AK♠+", "AA-♠♠", "漢字", "🂡🂮",
+                  "A\u{301}K", "é", "AKs,♠", "\u{0}\u{0}", "ＡＫ", "AK\u{301}"];
+    for input in inputs.iter() {
+        check_input(input);
+        assert!(SimpleRange::new_from_string(input).is_err(), "'{}' should not parse", input);
+    }
+}
+
+/// Pieces which make up random ranges for the round trip.
+const COMPONENTS : &[&str] = &[
+    "AA", "KK", "77", "22", "TT+", "55-88", "AKs", "AKo", "KQ", "A2s", "T9s+", "K5o+",
+    "A2s-A5s", "54s-98s", "Axs", "Qxo", "AhKh", "7c7d", "AsXs", "KhKx", "8[shd]8[shd]",
+    "AxKy", "xxs",
+];
+
+fn random_range_text<R: Rng>(rng: &mut R) -> String {
+    let count = rng.gen_range(1, 6);
+    let mut parts : Vec<String> = Vec::new();
+    for _ in 0..count {
+        parts.push(rng.choose(COMPONENTS).unwrap().to_string());
+    }
+    parts.join(",")
+}
+
+#[test]
+fn canonical_text_round_trip() {
+    let mut rng = XorShiftRng::from_seed([4, 3, 2, 1]);
+    for _ in 0..2000 {
+        let text = random_range_text(&mut rng);
+        let range = SimpleRange::new_from_string(&text).unwrap();
+
+        let canonical = range.to_string();
+        let parsed : SimpleRange = canonical.parse().unwrap();
+        assert_eq!(parsed, range, "'{}' became '{}'", text, canonical);
+        assert_eq!(parsed.to_string(), canonical);
+    }
+}